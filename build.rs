@@ -1,9 +1,279 @@
+use std::fmt::Write;
 use std::path::Path;
 
+// The figures of the boards and of the manifest, shared with the crate and pip-image.
+#[allow(dead_code)]
+#[path = "src/boot_manifest/format.rs"]
+mod format;
+#[allow(dead_code)]
+#[path = "src/board/specs.rs"]
+mod specs;
+// The parser of pip's C header, tested with the crate.
+#[allow(dead_code)]
+#[path = "src/core/abi_parser.rs"]
+mod abi_parser;
+
+use format::MAX_CHILDREN;
+use specs::{BOARDS, MPU_MIN_REGION_SIZE, PARTITION_MPU_REGIONS, PIP_BLOCKS_SIZE};
+
+/// The C header of pip's ABI, which `src/core/abi.rs` checks `src/core/pip_items.rs` against.
+const PIP_HEADER: &str = "relocation_tools/relocator/pip-mpu.h";
+/// The header's defines the crate duplicates.
+const PIP_DEFINES: [&str; 3] = [
+    "VIDT_INTERRUPT_NUMBER",
+    "BASIC_FRAME_SIZE",
+    "EXTENDED_FRAME_SIZE",
+];
+
+struct Child {
+    name: String,
+    stack_size: usize,
+    extra_ram: usize,
+    shm_count: usize, //The links of the child plus those naming it as their peer
+    shm_peers: Vec<String>,
+    shm_ram: usize, //The RAM of the child's shared blocks in the worst case, once aligned on their size
+}
+
+fn fail(message: String) -> ! {
+    panic!("partitions.toml: {message}")
+}
+
+fn number(table: &toml::Table, key: &str, default: Option<usize>) -> usize {
+    match table.get(key) {
+        Some(toml::Value::Integer(value)) if *value >= 0 => *value as usize,
+        Some(_) => fail(format!("{key} must be a non-negative integer")),
+        None => default.unwrap_or_else(|| fail(format!("missing {key}"))),
+    }
+}
+
+/// The RAM a child needs in the worst case, when its RAM block starts right after an address
+/// aligned on its stack/vidt block size.
+fn child_ram_worst_case(
+    child: &Child,
+    vidt_size: usize,
+    context_size: usize,
+    interface_size: usize,
+) -> usize {
+    let stack_vidt_block_size = (child.stack_size + vidt_size).next_power_of_two();
+    (stack_vidt_block_size - MPU_MIN_REGION_SIZE)
+        + stack_vidt_block_size
+        + (context_size + interface_size).next_multiple_of(MPU_MIN_REGION_SIZE)
+        + child.extra_ram.next_multiple_of(MPU_MIN_REGION_SIZE)
+        + PIP_BLOCKS_SIZE
+}
+
+fn abi_fail(message: String) -> ! {
+    panic!("{PIP_HEADER}: {message}")
+}
+
 fn main() {
     let local_path = Path::new(env!("CARGO_MANIFEST_DIR"));
     println!(
         "cargo:rerun-if-changed={}",
         local_path.join("link.ld").display()
-    )
+    );
+
+    let header_path = local_path.join(PIP_HEADER);
+    println!("cargo:rerun-if-changed={}", header_path.display());
+    let header = std::fs::read_to_string(&header_path).unwrap_or_else(|e| abi_fail(e.to_string()));
+    let pointer_size = std::env::var("CARGO_CFG_TARGET_POINTER_WIDTH")
+        .unwrap()
+        .parse::<usize>()
+        .unwrap()
+        / 8;
+    let abi = abi_parser::parse(&header, pointer_size).unwrap_or_else(|e| abi_fail(e));
+    let generated = abi.to_rust(&PIP_DEFINES).unwrap_or_else(|e| abi_fail(e));
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    std::fs::write(Path::new(&out_dir).join("pip_abi.rs"), generated).unwrap();
+
+    // Only partition management uses the configuration.
+    if std::env::var_os("CARGO_FEATURE_MANAGE_PARTITION").is_none() {
+        return;
+    }
+
+    let config_path = local_path.join("partitions.toml");
+    println!("cargo:rerun-if-changed={}", config_path.display());
+    let config: toml::Table = std::fs::read_to_string(&config_path)
+        .unwrap_or_else(|e| fail(e.to_string()))
+        .parse()
+        .unwrap_or_else(|e: toml::de::Error| fail(e.to_string()));
+
+    // Selecting no board or several is reported by src/board, the first board is used
+    // meanwhile so that this error is the only one.
+    let spec = BOARDS
+        .iter()
+        .find(|spec| {
+            let feature = spec.name.to_uppercase().replace('-', "_");
+            std::env::var_os(format!("CARGO_FEATURE_{feature}")).is_some()
+        })
+        .unwrap_or(&BOARDS[0]);
+    let board = spec.name;
+    let (board_vidt_size, board_mpu_region_count, board_ram_size) =
+        (spec.vidt_size, spec.mpu_region_count, spec.ram_size);
+    let vidt_struct_size = 4 * (1 + spec.interrupt_number);
+    let layout_size = |name: &str| {
+        abi.layout(name)
+            .unwrap_or_else(|| abi_fail(format!("missing {name}")))
+            .size
+    };
+    // Children are booted with a basic context, see CHILD_CONTEXT_KIND.
+    let context_size = layout_size("basicContext_t");
+    let interface_size = layout_size("interface_t");
+
+    let rom_start = number(&config, "rom_start", None);
+    let vidt_size = number(&config, "vidt_size", Some(board_vidt_size));
+    let mpu_region_count = number(&config, "mpu_region_count", Some(board_mpu_region_count));
+    let ram_size = number(&config, "ram_size", Some(board_ram_size / 2));
+    let stack_size = number(&config, "stack_size", Some(512));
+
+    let mut children: Vec<Child> = match config.get("child") {
+        None => Vec::new(),
+        Some(toml::Value::Array(children)) => children
+            .iter()
+            .map(|child| {
+                let child = child
+                    .as_table()
+                    .unwrap_or_else(|| fail("child must be a table".to_string()));
+                let links = child
+                    .get("shm")
+                    .and_then(|shm| shm.as_array())
+                    .map_or(&[][..], |shm| shm.as_slice());
+                Child {
+                    name: child
+                        .get("name")
+                        .and_then(|name| name.as_str())
+                        .unwrap_or_else(|| fail("child without a name".to_string()))
+                        .to_string(),
+                    stack_size: number(child, "stack_size", Some(stack_size)),
+                    extra_ram: number(child, "extra_ram", Some(0)),
+                    shm_count: 0,
+                    shm_peers: links
+                        .iter()
+                        .map(|link| {
+                            link.get("peer")
+                                .and_then(|peer| peer.as_str())
+                                .unwrap_or_else(|| fail("shm without a peer".to_string()))
+                                .to_string()
+                        })
+                        .collect(),
+                    shm_ram: links
+                        .iter()
+                        .map(|link| {
+                            let size = link
+                                .as_table()
+                                .map(|link| number(link, "size", None))
+                                .unwrap_or_else(|| fail("shm must be a table".to_string()))
+                                .max(MPU_MIN_REGION_SIZE)
+                                .next_power_of_two();
+                            2 * size - MPU_MIN_REGION_SIZE
+                        })
+                        .sum(),
+                }
+            })
+            .collect(),
+        Some(_) => fail("child must be an array of tables".to_string()),
+    };
+
+    if !rom_start.is_multiple_of(MPU_MIN_REGION_SIZE) {
+        fail(format!(
+            "rom_start {rom_start:#x} is not aligned on {MPU_MIN_REGION_SIZE} bytes"
+        ));
+    }
+    if vidt_size < board_vidt_size.max(vidt_struct_size) || !vidt_size.is_multiple_of(4) {
+        fail(format!(
+            "vidt_size {vidt_size} must be a multiple of 4, at least the {board_vidt_size} bytes pip reserves on {board} and the {vidt_struct_size} bytes of its VIDT"
+        ));
+    }
+    if mpu_region_count > board_mpu_region_count {
+        fail(format!(
+            "mpu_region_count {mpu_region_count}, {board} only has {board_mpu_region_count}"
+        ));
+    }
+    if ram_size > board_ram_size {
+        fail(format!(
+            "ram_size {ram_size:#x}, {board} only has {board_ram_size:#x} bytes of RAM"
+        ));
+    }
+    if mpu_region_count < PARTITION_MPU_REGIONS {
+        fail(format!(
+            "mpu_region_count {mpu_region_count} is lower than the {PARTITION_MPU_REGIONS} regions of a partition"
+        ));
+    }
+    if children.len() > MAX_CHILDREN {
+        fail(format!(
+            "{} children, at most {MAX_CHILDREN} are supported",
+            children.len()
+        ));
+    }
+
+    // A shared block takes an MPU region in both children of the link.
+    for index in 0..children.len() {
+        let name = &children[index].name;
+        let as_peer = children
+            .iter()
+            .flat_map(|other| &other.shm_peers)
+            .filter(|peer| *peer == name)
+            .count();
+        children[index].shm_count = children[index].shm_peers.len() + as_peer;
+    }
+
+    let mut children_ram_size = 0;
+    for child in &children {
+        if child.stack_size == 0 || !child.stack_size.is_multiple_of(4) {
+            fail(format!(
+                "{}: stack_size {} must be a non zero multiple of 4",
+                child.name, child.stack_size
+            ));
+        }
+        if PARTITION_MPU_REGIONS + child.shm_count > mpu_region_count {
+            fail(format!(
+                "{}: {} shared blocks, only {} MPU regions are left",
+                child.name,
+                child.shm_count,
+                mpu_region_count - PARTITION_MPU_REGIONS
+            ));
+        }
+        let needed = child_ram_worst_case(child, vidt_size, context_size, interface_size);
+        if needed > ram_size {
+            fail(format!(
+                "{}: a stack of {} bytes plus a vidt of {vidt_size} bytes cannot be aligned in {ram_size:#x} bytes of RAM",
+                child.name, child.stack_size
+            ));
+        }
+        children_ram_size += needed + child.shm_ram;
+    }
+    if children_ram_size > ram_size {
+        fail(format!(
+            "children and their shared blocks need up to {children_ram_size:#x} bytes of RAM, only {ram_size:#x} are available"
+        ));
+    }
+
+    let mut generated = String::new();
+    for (doc, name, value) in [
+        (
+            "The size of a VIDT and its interrupt stack.",
+            "VIDT_SIZE",
+            vidt_size,
+        ),
+        (
+            "The number of MPU regions of the board.",
+            "MPU_REGION_COUNT",
+            mpu_region_count,
+        ),
+        ("The RAM available to the children.", "RAM_SIZE", ram_size),
+        (
+            "The stack size of a child whose manifest entry leaves it at 0.",
+            "STACK_SIZE",
+            stack_size,
+        ),
+    ] {
+        writeln!(
+            generated,
+            "/// {doc}\npub const {name}: usize = {value:#x};"
+        )
+        .unwrap();
+    }
+
+    let out_path = Path::new(&std::env::var("OUT_DIR").unwrap()).join("partitions_config.rs");
+    std::fs::write(out_path, generated).unwrap();
 }
@@ -0,0 +1,245 @@
+//! The system composition, described in `partitions.toml`.
+//!
+//! ```toml
+//! rom_start = 0x7100
+//! vidt_size = 512          # only read by build.rs
+//! mpu_region_count = 8     # 5 regions per partition, the others hold shared blocks
+//! ram_size = 0x8000        # only read by build.rs
+//! stack_size = 512         # default child stack size
+//!
+//! [[child]]
+//! name = "sensor"
+//! image = "../sensor/partition-release.bin"
+//! stack_size = 1024        # optional, defaults to the global stack_size
+//! extra_ram = 4096
+//! rom_size = 0x4000        # optional, defaults to the image size
+//! restart = "on-fault"     # never, always or on-fault, defaults to never
+//! irq = [{ interrupt = 17, vidt_index = 1 }]
+//! shm = [{ peer = "radio", size = 256, rights = "rw", peer_rights = "r" }]
+//! ```
+//!
+//! Shared blocks cannot be both writable and executable for a child, unless the link sets
+//! `allow_write_exec = true`.
+
+use crate::layout::{align_up, MPU_MIN_REGION_SIZE};
+use crate::manifest::{self, Child, IrqRoute, RestartPolicy, ShmLink};
+use crate::specs::PARTITION_MPU_REGIONS;
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub rom_start: Option<usize>,
+    pub mpu_region_count: Option<usize>,
+    pub stack_size: Option<u32>,
+    #[serde(rename = "vidt_size")]
+    _vidt_size: Option<IgnoredAny>,
+    #[serde(rename = "ram_size")]
+    _ram_size: Option<IgnoredAny>,
+    #[serde(default, rename = "child")]
+    pub children: Vec<ChildConfig>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChildConfig {
+    pub name: String,
+    pub image: PathBuf,
+    pub stack_size: Option<u32>,
+    #[serde(default)]
+    pub extra_ram: u32,
+    pub rom_size: Option<u32>,
+    #[serde(default)]
+    pub restart: Restart,
+    #[serde(default)]
+    pub irq: Vec<IrqConfig>,
+    #[serde(default)]
+    pub shm: Vec<ShmConfig>,
+}
+
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum Restart {
+    #[default]
+    Never,
+    Always,
+    OnFault,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IrqConfig {
+    pub interrupt: u32,
+    pub vidt_index: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ShmConfig {
+    pub peer: String,
+    pub size: u32,
+    pub rights: String,
+    pub peer_rights: String,
+    #[serde(default)]
+    pub allow_write_exec: bool,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Config, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        let mut config: Config =
+            toml::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))?;
+
+        // Images are relative to the configuration file.
+        let dir = path.parent().unwrap_or(Path::new("."));
+        for child in &mut config.children {
+            child.image = dir.join(&child.image);
+        }
+        Ok(config)
+    }
+
+    /// Reads the child images and lays them out after the manifest.
+    ///
+    /// Returns the manifest description of every child along with its image.
+    pub fn children(&self) -> Result<Vec<(Child, Vec<u8>)>, String> {
+        let index_of = |name: &str| {
+            self.children
+                .iter()
+                .position(|child| child.name == name)
+                .ok_or_else(|| format!("{name}: no child with this name"))
+        };
+
+        let mut children = Vec::new();
+        for config in &self.children {
+            let image = std::fs::read(&config.image)
+                .map_err(|e| format!("{}: {e}", config.image.display()))?;
+            let rom_size = config.rom_size.unwrap_or(image.len() as u32);
+            if (image.len() as u32) > rom_size {
+                return Err(format!(
+                    "{}: image ({:#x} bytes) larger than rom_size ({rom_size:#x} bytes)",
+                    config.name,
+                    image.len()
+                ));
+            }
+
+            let stack_size = config
+                .stack_size
+                .or(self.stack_size)
+                .ok_or_else(|| format!("{}: no stack_size", config.name))?;
+            if let Some(mpu_region_count) = self.mpu_region_count {
+                // A link takes a region in both children, the one declaring it and its peer.
+                let shared_blocks = config.shm.len()
+                    + self
+                        .children
+                        .iter()
+                        .flat_map(|other| &other.shm)
+                        .filter(|link| link.peer == config.name)
+                        .count();
+                let free_regions = mpu_region_count.saturating_sub(PARTITION_MPU_REGIONS);
+                if shared_blocks > free_regions {
+                    return Err(format!(
+                        "{}: {shared_blocks} shared blocks, only {free_regions} MPU regions are left",
+                        config.name
+                    ));
+                }
+            }
+
+            let shm_links = config
+                .shm
+                .iter()
+                .map(|link| {
+                    Ok(ShmLink {
+                        peer: index_of(&link.peer)? as u32,
+                        size: link.size,
+                        rights: manifest::pack_rights(&link.rights, link.allow_write_exec)
+                            .map_err(|e| format!("{}: {e}", config.name))?,
+                        peer_rights: manifest::pack_rights(
+                            &link.peer_rights,
+                            link.allow_write_exec,
+                        )
+                        .map_err(|e| format!("{}: {e}", config.name))?,
+                    })
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+
+            children.push((
+                Child {
+                    name: config.name.clone(),
+                    image_offset: 0,
+                    image_size: image.len() as u32,
+                    rom_size: align_up(rom_size as usize, MPU_MIN_REGION_SIZE) as u32,
+                    stack_size,
+                    extra_ram: config.extra_ram,
+                    restart_policy: match config.restart {
+                        Restart::Never => RestartPolicy::Never,
+                        Restart::Always => RestartPolicy::Always,
+                        Restart::OnFault => RestartPolicy::OnFault,
+                    },
+                    irq_routes: config
+                        .irq
+                        .iter()
+                        .map(|route| IrqRoute {
+                            interrupt: route.interrupt,
+                            vidt_index: route.vidt_index,
+                        })
+                        .collect(),
+                    shm_links,
+                },
+                image,
+            ));
+        }
+
+        let descriptions: Vec<Child> = children.iter().map(|(child, _)| child.clone()).collect();
+        let mut offset = manifest::first_image_offset(&descriptions);
+        for (child, _) in &mut children {
+            child.image_offset = offset as u32;
+            offset += child.rom_size as usize;
+        }
+        Ok(children)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A configuration whose children share blocks as `links` tells, each name pointing to its
+    /// peers, with images written to a temporary directory.
+    fn config(name: &str, mpu_region_count: usize, links: &[(&str, &[&str])]) -> Config {
+        let dir = std::env::temp_dir().join(format!("pip-image-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut text = format!("mpu_region_count = {mpu_region_count}\nstack_size = 512\n");
+        for (child, peers) in links {
+            std::fs::write(dir.join(child), [0_u8; 16]).unwrap();
+            text += &format!("[[child]]\nname = \"{child}\"\nimage = \"{child}\"\nshm = [\n");
+            for peer in *peers {
+                text += &format!(
+                    "  {{ peer = \"{peer}\", size = 32, rights = \"rw\", peer_rights = \"r\" }},\n"
+                );
+            }
+            text += "]\n";
+        }
+        let path = dir.join("partitions.toml");
+        std::fs::write(&path, text).unwrap();
+        Config::load(&path).unwrap()
+    }
+
+    #[test]
+    fn shared_blocks_fit() {
+        let config = config("fit", 7, &[("a", &["b", "c"]), ("b", &[]), ("c", &[])]);
+        let children = config.children().unwrap();
+        assert_eq!(children[0].0.shm_links.len(), 2);
+        assert_eq!(children[0].0.shm_links[1].peer, 2);
+        assert_eq!(children[1].0.image_offset, children[0].0.image_offset + 32);
+    }
+
+    #[test]
+    fn shared_blocks_count_for_the_peer() {
+        // b declares a single link, but is the peer of a and c.
+        let config = config("peer", 7, &[("a", &["b"]), ("b", &["c"]), ("c", &["b"])]);
+        let error = config.children().err().unwrap();
+        assert_eq!(error, "b: 3 shared blocks, only 2 MPU regions are left");
+    }
+}
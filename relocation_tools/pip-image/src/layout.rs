@@ -0,0 +1,429 @@
+//! Layout of a partition image, as produced by the relocator scripts :
+//!
+//! ```text
+//! +------------+ <- ROM_START
+//! | crt0       |
+//! +------------+
+//! | symbols    | -> symbolTable_t of crt0.h, 6 words
+//! +------------+
+//! | relocation | -> patchinfoTable_t of crt0.h, a count followed by as many offsets
+//! +------------+ <- vtable patching offset
+//! | binary     | -> .rom, .rom.ram and .ARM.exidx of the partition
+//! +------------+
+//! ```
+//!
+//! In a flashable image, the partition is preceded by pip, which fills the flash up to
+//! ROM_START, and followed by the boot manifest and the child images.
+
+use crate::manifest::{self, Child};
+pub use crate::specs::MPU_MIN_REGION_SIZE;
+
+/// The default state of non-volatile NAND flash memories.
+pub const PADDING_VALUE: u8 = 0xff;
+
+pub const SYMBOLS_SIZE: usize = 6 * 4;
+
+pub fn align_up(x: usize, align: usize) -> usize {
+    (x + align - 1) & !(align - 1)
+}
+
+pub fn read_word(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|w| u32::from_le_bytes([w[0], w[1], w[2], w[3]]))
+}
+
+pub fn pad_to(data: &mut Vec<u8>, size: usize) {
+    if data.len() < size {
+        data.resize(size, PADDING_VALUE);
+    }
+}
+
+/// The symbol table written by `symbols.py`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbols {
+    pub entry_point: u32,
+    pub rom_size: u32,
+    pub rom_ram_size: u32,
+    pub ram_size: u32,
+    pub got_size: u32,
+    pub rom_ram_end: u32,
+}
+
+impl Symbols {
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        Some(Symbols {
+            entry_point: read_word(data, 0)?,
+            rom_size: read_word(data, 4)?,
+            rom_ram_size: read_word(data, 8)?,
+            ram_size: read_word(data, 12)?,
+            got_size: read_word(data, 16)?,
+            rom_ram_end: read_word(data, 20)?,
+        })
+    }
+}
+
+/// The parts of an image, every offset being relative to the start of the file.
+#[derive(Debug)]
+pub struct Layout {
+    pub file_size: usize,
+    pub rom_start: usize,
+    pub partition_offset: usize,
+    pub crt0_size: usize,
+    pub symbols: Symbols,
+    pub relocation_count: usize,
+    pub binary_offset: usize,
+    pub manifest_offset: Option<usize>,
+    pub children: Vec<Child>,
+}
+
+impl Layout {
+    /// Splits `data` into its parts.
+    ///
+    /// `partition_offset` is 0 for a lone partition image, and ROM_START for a flashable image
+    /// starting with pip.
+    pub fn parse(
+        data: &[u8],
+        rom_start: usize,
+        partition_offset: usize,
+        crt0_size: usize,
+    ) -> Result<Layout, String> {
+        let symbols_offset = partition_offset + crt0_size;
+        let symbols = data
+            .get(symbols_offset..)
+            .and_then(Symbols::parse)
+            .ok_or("image too small to hold the symbol table")?;
+
+        let relocation_offset = symbols_offset + SYMBOLS_SIZE;
+        let relocation_count = read_word(data, relocation_offset)
+            .ok_or("image too small to hold the relocation table")?
+            as usize;
+        let binary_offset = relocation_offset + 4 + relocation_count * 4;
+        if binary_offset > data.len() {
+            return Err(format!(
+                "{relocation_count} relocations exceed the image size"
+            ));
+        }
+
+        let mut layout = Layout {
+            file_size: data.len(),
+            rom_start,
+            partition_offset,
+            crt0_size,
+            symbols,
+            relocation_count,
+            binary_offset,
+            manifest_offset: None,
+            children: Vec::new(),
+        };
+
+        // The manifest lies on the first aligned address after the binary, whose
+        // .ARM.exidx size is unknown, so every aligned address is tried.
+        let mut offset = align_up(layout.binary_min_end(), MPU_MIN_REGION_SIZE);
+        while offset + manifest::HEADER_SIZE <= data.len() {
+            if read_word(data, offset) == Some(manifest::MANIFEST_MAGIC) {
+                layout.children = manifest::read(&data[offset..])?;
+                layout.manifest_offset = Some(offset);
+                break;
+            }
+            offset += MPU_MIN_REGION_SIZE;
+        }
+        Ok(layout)
+    }
+
+    /// The offset at which the relocator patches vtables, i.e. the address of the binary in ROM.
+    pub fn vtable_offset(&self) -> usize {
+        self.rom_start + self.binary_offset - self.partition_offset
+    }
+
+    /// The end of the binary, .ARM.exidx excluded.
+    pub fn binary_min_end(&self) -> usize {
+        self.binary_offset + self.symbols.rom_size as usize + self.symbols.rom_ram_size as usize
+    }
+
+    /// Checks sizes and alignments, returning every problem found.
+    pub fn check(&self, flash_size: Option<usize>) -> Vec<String> {
+        let mut problems = Vec::new();
+        let mut problem = |message: String| problems.push(message);
+        let symbols = &self.symbols;
+
+        if !self.rom_start.is_multiple_of(MPU_MIN_REGION_SIZE) {
+            problem(format!(
+                "ROM_START {:#x} is not aligned on {MPU_MIN_REGION_SIZE} bytes",
+                self.rom_start
+            ));
+        }
+        if !self.crt0_size.is_multiple_of(4) {
+            problem(format!(
+                "crt0 size {:#x} is not word aligned",
+                self.crt0_size
+            ));
+        }
+        for (name, size) in [
+            (".rom", symbols.rom_size),
+            (".got", symbols.got_size),
+            (".rom.ram", symbols.rom_ram_size),
+            (".ram", symbols.ram_size),
+        ] {
+            if !size.is_multiple_of(4) {
+                problem(format!("{name} size {size:#x} is not word aligned"));
+            }
+        }
+        if symbols.entry_point >= symbols.rom_size {
+            problem(format!(
+                "entry point {:#x} lies outside of .rom ({:#x} bytes)",
+                symbols.entry_point, symbols.rom_size
+            ));
+        }
+        if symbols.rom_ram_end != symbols.rom_size + symbols.got_size + symbols.rom_ram_size {
+            problem(format!(
+                "__romRamEnd {:#x} does not match .rom + .got + .rom.ram",
+                symbols.rom_ram_end
+            ));
+        }
+        if self.binary_min_end() > self.file_size {
+            problem(format!(
+                "binary ends at {:#x}, after the end of the image",
+                self.binary_min_end()
+            ));
+        }
+        if let Some(flash_size) = flash_size {
+            if self.file_size > flash_size {
+                problem(format!(
+                    "image is {:#x} bytes long, flash is {flash_size:#x}",
+                    self.file_size
+                ));
+            }
+        }
+
+        let Some(manifest_offset) = self.manifest_offset else {
+            problem("no boot manifest found after the root binary".to_string());
+            return problems;
+        };
+        let images_start = manifest_offset + manifest::manifest_size(&self.children);
+        let mut previous_end = images_start;
+        for (index, child) in self.children.iter().enumerate() {
+            let start = manifest_offset + child.image_offset as usize;
+            if !(child.image_offset as usize).is_multiple_of(MPU_MIN_REGION_SIZE) {
+                problem(format!(
+                    "{}: image offset {:#x} is not aligned on {MPU_MIN_REGION_SIZE} bytes",
+                    child.name, child.image_offset
+                ));
+            }
+            if child.image_size > child.rom_size {
+                problem(format!(
+                    "{}: image ({:#x} bytes) larger than its ROM ({:#x} bytes)",
+                    child.name, child.image_size, child.rom_size
+                ));
+            }
+            if start < previous_end {
+                problem(format!(
+                    "{}: image overlaps the manifest or the previous image",
+                    child.name
+                ));
+            }
+            if start + child.image_size as usize > self.file_size {
+                problem(format!("{}: image exceeds the end of the file", child.name));
+            }
+            // 0 stands for the root's default stack size.
+            if !child.stack_size.is_multiple_of(4) {
+                problem(format!(
+                    "{}: stack_size {} is not a multiple of 4",
+                    child.name, child.stack_size
+                ));
+            }
+            for link in &child.shm_links {
+                if link.peer as usize >= self.children.len() || link.peer as usize == index {
+                    problem(format!(
+                        "{}: shares memory with invalid peer {}",
+                        child.name, link.peer
+                    ));
+                }
+            }
+            previous_end = start + child.rom_size as usize;
+        }
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::{IrqRoute, RestartPolicy, ShmLink};
+
+    const ROM_START: usize = 0x7100;
+    const CRT0_SIZE: usize = 8;
+
+    fn symbols() -> Symbols {
+        Symbols {
+            entry_point: 0x10,
+            rom_size: 0x40,
+            rom_ram_size: 0x8,
+            ram_size: 0x20,
+            got_size: 0x4,
+            rom_ram_end: 0x4c,
+        }
+    }
+
+    fn child(name: &str) -> Child {
+        Child {
+            name: name.to_string(),
+            image_offset: 0,
+            image_size: 0x30,
+            rom_size: 0x40,
+            stack_size: 512,
+            extra_ram: 0,
+            restart_policy: RestartPolicy::Never,
+            irq_routes: vec![IrqRoute {
+                interrupt: 3,
+                vidt_index: 3,
+            }],
+            shm_links: Vec::new(),
+        }
+    }
+
+    /// A partition image with `relocations` relocations, followed by the manifest of `children`
+    /// and their images if `children` is given.
+    fn image(symbols: Symbols, relocations: u32, children: Option<&mut [Child]>) -> Vec<u8> {
+        let mut data = vec![0; CRT0_SIZE];
+        for word in [
+            symbols.entry_point,
+            symbols.rom_size,
+            symbols.rom_ram_size,
+            symbols.ram_size,
+            symbols.got_size,
+            symbols.rom_ram_end,
+            relocations,
+        ] {
+            data.extend(word.to_le_bytes());
+        }
+        data.extend((0..relocations).flat_map(|i| (i * 4).to_le_bytes()));
+        data.resize(
+            data.len() + (symbols.rom_size + symbols.rom_ram_size) as usize,
+            0,
+        );
+
+        if let Some(children) = children {
+            let manifest_offset = align_up(data.len(), MPU_MIN_REGION_SIZE);
+            pad_to(&mut data, manifest_offset);
+            let mut offset = manifest::first_image_offset(children) as u32;
+            for child in children.iter_mut() {
+                child.image_offset = offset;
+                offset += child.rom_size;
+            }
+            data.extend(manifest::write(children).unwrap());
+            for child in children.iter() {
+                pad_to(&mut data, manifest_offset + child.image_offset as usize);
+                data.resize(data.len() + child.image_size as usize, 0xaa);
+                pad_to(
+                    &mut data,
+                    manifest_offset + (child.image_offset + child.rom_size) as usize,
+                );
+            }
+        }
+        data
+    }
+
+    fn parse(data: &[u8]) -> Layout {
+        Layout::parse(data, ROM_START, 0, CRT0_SIZE).unwrap()
+    }
+
+    #[test]
+    fn parse_finds_every_part() {
+        let mut children = [child("sensor"), child("radio")];
+        let data = image(symbols(), 2, Some(&mut children));
+        let layout = parse(&data);
+
+        assert_eq!(layout.symbols, symbols());
+        assert_eq!(layout.relocation_count, 2);
+        assert_eq!(layout.binary_offset, CRT0_SIZE + SYMBOLS_SIZE + 4 + 2 * 4);
+        assert_eq!(layout.vtable_offset(), ROM_START + layout.binary_offset);
+        assert_eq!(
+            layout.manifest_offset,
+            Some(align_up(layout.binary_min_end(), MPU_MIN_REGION_SIZE))
+        );
+        assert_eq!(layout.children, children);
+        assert!(layout.check(None).is_empty(), "{:?}", layout.check(None));
+    }
+
+    #[test]
+    fn parse_refuses_truncated_images() {
+        let data = image(symbols(), 0, None);
+        assert!(Layout::parse(&data[..CRT0_SIZE + 8], ROM_START, 0, CRT0_SIZE).is_err());
+        let data = image(symbols(), 0x1000, None);
+        let end = CRT0_SIZE + SYMBOLS_SIZE + 4;
+        assert!(Layout::parse(&data[..end], ROM_START, 0, CRT0_SIZE).is_err());
+    }
+
+    #[test]
+    fn check_without_manifest() {
+        let layout = parse(&image(symbols(), 0, None));
+        assert_eq!(
+            layout.check(None),
+            ["no boot manifest found after the root binary"]
+        );
+    }
+
+    #[test]
+    fn check_symbols() {
+        let mut symbols = symbols();
+        symbols.entry_point = symbols.rom_size;
+        symbols.got_size = 2;
+        let layout = parse(&image(symbols, 0, Some(&mut [])));
+        let problems = layout.check(None);
+        assert_eq!(problems.len(), 3, "{problems:?}");
+        assert!(problems[0].starts_with(".got size"));
+        assert!(problems[1].starts_with("entry point"));
+        assert!(problems[2].starts_with("__romRamEnd"));
+    }
+
+    #[test]
+    fn check_alignments_and_flash_size() {
+        let data = image(symbols(), 0, Some(&mut []));
+        let layout = Layout::parse(&data, ROM_START + 4, 0, CRT0_SIZE).unwrap();
+        let problems = layout.check(Some(data.len() - 1));
+        assert_eq!(problems.len(), 2, "{problems:?}");
+        assert!(problems[0].starts_with("ROM_START"));
+        assert!(problems[1].starts_with("image is"));
+    }
+
+    #[test]
+    fn check_children() {
+        let mut children = [child("sensor"), child("radio")];
+        children[1].shm_links.push(ShmLink {
+            peer: 1,
+            size: 32,
+            rights: 0b100,
+            peer_rights: 0b100,
+        });
+        let data = image(symbols(), 0, Some(&mut children));
+        let mut layout = parse(&data);
+
+        layout.children[0].image_size = 0x50;
+        layout.children[0].stack_size = 6;
+        layout.children[1].image_offset -= 0x20;
+        let problems = layout.check(None);
+        assert_eq!(
+            problems,
+            [
+                "sensor: image (0x50 bytes) larger than its ROM (0x40 bytes)",
+                "sensor: stack_size 6 is not a multiple of 4",
+                "radio: image overlaps the manifest or the previous image",
+                "radio: shares memory with invalid peer 1",
+            ]
+        );
+
+        layout.children[1].image_offset += 0x30;
+        assert!(layout.check(None)[2].ends_with("is not aligned on 32 bytes"));
+    }
+
+    #[test]
+    fn check_children_beyond_the_file() {
+        let mut children = [child("sensor")];
+        let data = image(symbols(), 0, Some(&mut children));
+        let layout = parse(&data[..data.len() - 0x20]);
+        assert_eq!(
+            layout.check(None),
+            ["sensor: image exceeds the end of the file"]
+        );
+    }
+}
@@ -0,0 +1,322 @@
+//! Host tool composing and inspecting pip+root images.
+//!
+//! It replaces the unchecked `cat` of the build : every image it writes is parsed back and
+//! checked before being saved.
+
+mod config;
+mod layout;
+mod manifest;
+
+// The figures of the boards and of the manifest, shared with pip-mpu.
+#[allow(dead_code)]
+#[path = "../../../src/boot_manifest/format.rs"]
+mod format;
+#[allow(dead_code)]
+#[path = "../../../src/board/specs.rs"]
+mod specs;
+
+use config::Config;
+use layout::{align_up, pad_to, Layout, MPU_MIN_REGION_SIZE, SYMBOLS_SIZE};
+use std::path::{Path, PathBuf};
+use std::process::exit;
+
+const DEFAULT_CONFIG: &str = "partitions.toml";
+const DEFAULT_CRT0: &str = "relocation_tools/relocator/crt0.bin";
+
+const USAGE: &str = "\
+usage: pip-image partition [OPTIONS] CRT0 SYMBOLS RELOCATION BINARY OUTPUT
+       pip-image compose [OPTIONS] PIP PARTITION OUTPUT
+       pip-image dump [--partition-only] [OPTIONS] IMAGE
+       pip-image check [--partition-only] [--flash-size SIZE] [OPTIONS] IMAGE
+
+options:
+       --config FILE      system composition, partitions.toml by default
+       --rom-start ADDR   address of the root partition in flash, rom_start of the configuration
+                          by default
+       --crt0 FILE        crt0 binary, relocation_tools/relocator/crt0.bin by default
+       --crt0-size SIZE   crt0 size, instead of reading it from the crt0 binary";
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    exit(1)
+}
+
+fn die(message: impl std::fmt::Display) -> ! {
+    eprintln!("pip-image: {message}");
+    exit(1)
+}
+
+fn parse_number(s: &str) -> Result<usize, String> {
+    let parsed = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => usize::from_str_radix(hex, 16),
+        None => s.parse(),
+    };
+    parsed.map_err(|_| format!("{s}: not a number"))
+}
+
+fn read(path: &Path) -> Vec<u8> {
+    std::fs::read(path).unwrap_or_else(|e| die(format!("{}: {e}", path.display())))
+}
+
+#[derive(Default)]
+struct Options {
+    config: Option<PathBuf>,
+    rom_start: Option<usize>,
+    crt0: Option<PathBuf>,
+    crt0_size: Option<usize>,
+    flash_size: Option<usize>,
+    partition_only: bool,
+    positional: Vec<PathBuf>,
+}
+
+impl Options {
+    fn parse(args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options::default();
+        let mut args = args.peekable();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("{arg}: missing value"));
+            match arg.as_str() {
+                "--config" => options.config = Some(value()?.into()),
+                "--rom-start" => options.rom_start = Some(parse_number(&value()?)?),
+                "--crt0" => options.crt0 = Some(value()?.into()),
+                "--crt0-size" => options.crt0_size = Some(parse_number(&value()?)?),
+                "--flash-size" => options.flash_size = Some(parse_number(&value()?)?),
+                "--partition-only" => options.partition_only = true,
+                _ if arg.starts_with("--") => return Err(format!("{arg}: unknown option")),
+                _ => options.positional.push(arg.into()),
+            }
+        }
+        Ok(options)
+    }
+
+    fn crt0_size(&self) -> usize {
+        self.crt0_size.unwrap_or_else(|| {
+            let path = self
+                .crt0
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_CRT0));
+            std::fs::metadata(&path)
+                .unwrap_or_else(|e| die(format!("{}: {e}", path.display())))
+                .len() as usize
+        })
+    }
+
+    /// The configuration given with `--config`, or `partitions.toml` if there is one.
+    fn config(&self) -> Option<Config> {
+        let path = match &self.config {
+            Some(path) => path.clone(),
+            None => Some(PathBuf::from(DEFAULT_CONFIG)).filter(|path| path.exists())?,
+        };
+        Some(Config::load(&path).unwrap_or_else(|e| die(e)))
+    }
+
+    /// The `--rom-start` address, or the `rom_start` of `config`.
+    fn rom_start(&self, config: Option<&Config>) -> usize {
+        self.rom_start
+            .or(config.and_then(|config| config.rom_start))
+            .unwrap_or_else(|| die("no ROM_START, give --rom-start or a --config with rom_start"))
+    }
+
+    fn positional<const N: usize>(&self) -> [&Path; N] {
+        if self.positional.len() != N {
+            usage();
+        }
+        core::array::from_fn(|i| self.positional[i].as_path())
+    }
+}
+
+/// Concatenates the relocator outputs into a partition image, as `cat` used to.
+fn partition(options: &Options) {
+    let [crt0, symbols, relocation, binary, output] = options.positional();
+    let crt0 = read(crt0);
+    let symbols = read(symbols);
+    let relocation = read(relocation);
+    let binary = read(binary);
+
+    if !crt0.len().is_multiple_of(4) {
+        die(format!("crt0 size {:#x} is not word aligned", crt0.len()));
+    }
+    if symbols.len() != SYMBOLS_SIZE {
+        die(format!(
+            "symbol table is {:#x} bytes long, expected {SYMBOLS_SIZE:#x}",
+            symbols.len()
+        ));
+    }
+    let count = layout::read_word(&relocation, 0).unwrap_or_else(|| die("empty relocation table"));
+    if relocation.len() != 4 + count as usize * 4 {
+        die(format!(
+            "relocation table is {:#x} bytes long for {count} entries",
+            relocation.len()
+        ));
+    }
+
+    let crt0_size = crt0.len();
+    let image = [crt0, symbols, relocation, binary].concat();
+    let rom_start = options.rom_start(options.config().as_ref());
+    let layout = Layout::parse(&image, rom_start, 0, crt0_size).unwrap_or_else(|e| die(e));
+    write_checked(output, &image, &layout, true);
+}
+
+/// Writes pip, the root partition, the boot manifest and the child images in one flashable file.
+fn compose(options: &Options) {
+    let [pip, partition, output] = options.positional();
+    let config = options.config();
+    let rom_start = options.rom_start(config.as_ref());
+    let children = config
+        .as_ref()
+        .map(|config| config.children().unwrap_or_else(|e| die(e)))
+        .unwrap_or_default();
+
+    let mut image = read(pip);
+    if image.len() > rom_start {
+        die(format!(
+            "{}: {:#x} bytes long, it overlaps ROM_START {rom_start:#x}",
+            pip.display(),
+            image.len()
+        ));
+    }
+    pad_to(&mut image, rom_start);
+    image.extend(read(partition));
+
+    let manifest_offset = align_up(image.len(), MPU_MIN_REGION_SIZE);
+    pad_to(&mut image, manifest_offset);
+    let descriptions: Vec<_> = children.iter().map(|(child, _)| child.clone()).collect();
+    image.extend(manifest::write(&descriptions).unwrap_or_else(|e| die(e)));
+    for (child, child_image) in &children {
+        pad_to(&mut image, manifest_offset + child.image_offset as usize);
+        image.extend(child_image);
+        pad_to(
+            &mut image,
+            manifest_offset + (child.image_offset + child.rom_size) as usize,
+        );
+    }
+
+    let layout =
+        Layout::parse(&image, rom_start, rom_start, options.crt0_size()).unwrap_or_else(|e| die(e));
+    if layout.manifest_offset != Some(manifest_offset) {
+        die(format!(
+            "the manifest written at {manifest_offset:#x} could not be found back, is the crt0 size right ?"
+        ));
+    }
+    write_checked(output, &image, &layout, false);
+}
+
+fn write_checked(output: &Path, image: &[u8], layout: &Layout, partition_only: bool) {
+    let problems: Vec<_> = layout
+        .check(None)
+        .into_iter()
+        .filter(|problem| !(partition_only && problem.starts_with("no boot manifest")))
+        .collect();
+    if !problems.is_empty() {
+        for problem in &problems {
+            eprintln!("pip-image: {problem}");
+        }
+        die(format!("{}: not written", output.display()));
+    }
+    std::fs::write(output, image).unwrap_or_else(|e| die(format!("{}: {e}", output.display())));
+}
+
+fn load(options: &Options) -> Layout {
+    let [path] = options.positional();
+    let data = read(path);
+    let rom_start = options.rom_start(options.config().as_ref());
+    let partition_offset = if options.partition_only { 0 } else { rom_start };
+    Layout::parse(&data, rom_start, partition_offset, options.crt0_size())
+        .unwrap_or_else(|e| die(format!("{}: {e}", path.display())))
+}
+
+fn dump(options: &Options) {
+    let layout = load(options);
+    let addr = |offset: usize| layout.rom_start + offset - layout.partition_offset;
+    let symbols = &layout.symbols;
+    let relocation_offset = layout.partition_offset + layout.crt0_size + SYMBOLS_SIZE;
+
+    println!("ROM_START          {:#x}", layout.rom_start);
+    println!(
+        "crt0               {:#x} ({:#x} bytes)",
+        addr(layout.partition_offset),
+        layout.crt0_size
+    );
+    println!(
+        "symbols            {:#x}",
+        addr(layout.partition_offset + layout.crt0_size)
+    );
+    println!("  entry point      {:#x}", symbols.entry_point);
+    println!("  .rom             {:#x} bytes", symbols.rom_size);
+    println!("  .got             {:#x} bytes", symbols.got_size);
+    println!("  .rom.ram         {:#x} bytes", symbols.rom_ram_size);
+    println!("  .ram             {:#x} bytes", symbols.ram_size);
+    println!("  __romRamEnd      {:#x}", symbols.rom_ram_end);
+    println!(
+        "relocation         {:#x} ({} entries)",
+        addr(relocation_offset),
+        layout.relocation_count
+    );
+    println!("vtable offset      {:#x}", layout.vtable_offset());
+
+    let Some(manifest_offset) = layout.manifest_offset else {
+        println!("manifest           none");
+        return;
+    };
+    println!(
+        "manifest           {:#x} ({} children)",
+        addr(manifest_offset),
+        layout.children.len()
+    );
+    for (index, child) in layout.children.iter().enumerate() {
+        println!(
+            "  [{index}] {:<16} image {:#x} ({:#x} bytes), rom {:#x} bytes",
+            child.name,
+            addr(manifest_offset + child.image_offset as usize),
+            child.image_size,
+            child.rom_size
+        );
+        println!(
+            "      stack {:#x} bytes, extra ram {:#x} bytes, restart {}",
+            child.stack_size,
+            child.extra_ram,
+            child.restart_policy.name()
+        );
+        for route in &child.irq_routes {
+            println!("      irq {} -> vidt {}", route.interrupt, route.vidt_index);
+        }
+        for link in &child.shm_links {
+            let peer = layout
+                .children
+                .get(link.peer as usize)
+                .map_or("?", |peer| peer.name.as_str());
+            println!(
+                "      shm with {peer}: {:#x} bytes, {} / {}",
+                link.size,
+                manifest::unpack_rights(link.rights),
+                manifest::unpack_rights(link.peer_rights)
+            );
+        }
+    }
+}
+
+fn check(options: &Options) {
+    let layout = load(options);
+    let problems = layout.check(options.flash_size);
+    for problem in &problems {
+        eprintln!("pip-image: {problem}");
+    }
+    if !problems.is_empty() {
+        exit(1);
+    }
+    println!("ok");
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let command = args.next().unwrap_or_else(|| usage());
+    let options = Options::parse(args).unwrap_or_else(|e| die(e));
+    match command.as_str() {
+        "partition" => partition(&options),
+        "compose" => compose(&options),
+        "dump" => dump(&options),
+        "check" => check(&options),
+        "-h" | "--help" | "help" => println!("{USAGE}"),
+        _ => usage(),
+    }
+}
@@ -0,0 +1,429 @@
+//! Host side of the boot manifest, see `pip_mpu::boot_manifest::manifest_items`
+//! for the layout read by the root partition.
+
+use crate::layout::{align_up, read_word, MPU_MIN_REGION_SIZE};
+
+pub use crate::format::{CHILD_NAME_LEN, MANIFEST_MAGIC, MANIFEST_VERSION, MAX_CHILDREN};
+
+pub const HEADER_SIZE: usize = 5 * 4;
+pub const ENTRY_SIZE: usize = CHILD_NAME_LEN + 10 * 4;
+pub const IRQ_ROUTE_SIZE: usize = 2 * 4;
+pub const SHM_LINK_SIZE: usize = 4 * 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RestartPolicy {
+    Never = 0,
+    Always = 1,
+    OnFault = 2,
+}
+
+impl RestartPolicy {
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(RestartPolicy::Never),
+            1 => Some(RestartPolicy::Always),
+            2 => Some(RestartPolicy::OnFault),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RestartPolicy::Never => "never",
+            RestartPolicy::Always => "always",
+            RestartPolicy::OnFault => "on-fault",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IrqRoute {
+    pub interrupt: u32,
+    pub vidt_index: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShmLink {
+    pub peer: u32,
+    pub size: u32,
+    pub rights: u32,
+    pub peer_rights: u32,
+}
+
+/// A child as described in the manifest, its image excluded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Child {
+    pub name: String,
+    pub image_offset: u32,
+    pub image_size: u32,
+    pub rom_size: u32,
+    pub stack_size: u32,
+    pub extra_ram: u32,
+    pub restart_policy: RestartPolicy,
+    pub irq_routes: Vec<IrqRoute>,
+    pub shm_links: Vec<ShmLink>,
+}
+
+/// Set in packed rights when the child may get a block both writable and executable.
+pub const ALLOW_WRITE_EXEC: u32 = 0b1000;
+
+/// Packs `"rwx"` like strings as `(r << 2) | (w << 1) | x`, plus `ALLOW_WRITE_EXEC` if
+/// `allow_write_exec`.
+///
+/// Rights both writable and executable are refused unless `allow_write_exec`.
+pub fn pack_rights(rights: &str, allow_write_exec: bool) -> Result<u32, String> {
+    let packed = rights.chars().try_fold(0, |packed, c| match c {
+        'r' => Ok(packed | 0b100),
+        'w' => Ok(packed | 0b010),
+        'x' => Ok(packed | 0b001),
+        _ => Err(format!(
+            "{rights}: invalid rights, expected a subset of \"rwx\""
+        )),
+    })?;
+    if allow_write_exec {
+        Ok(packed | ALLOW_WRITE_EXEC)
+    } else if packed & 0b011 == 0b011 {
+        Err(format!(
+            "{rights}: writable and executable, set allow_write_exec to share it anyway"
+        ))
+    } else {
+        Ok(packed)
+    }
+}
+
+pub fn unpack_rights(rights: u32) -> String {
+    let mut unpacked: String = [(0b100, 'r'), (0b010, 'w'), (0b001, 'x')]
+        .iter()
+        .map(|(bit, c)| if rights & bit != 0 { *c } else { '-' })
+        .collect();
+    if rights & ALLOW_WRITE_EXEC != 0 {
+        unpacked.push_str(" (W^X off)");
+    }
+    unpacked
+}
+
+/// The size of the manifest data, child images excluded.
+pub fn manifest_size(children: &[Child]) -> usize {
+    HEADER_SIZE
+        + children.len() * ENTRY_SIZE
+        + children
+            .iter()
+            .map(|child| {
+                child.irq_routes.len() * IRQ_ROUTE_SIZE + child.shm_links.len() * SHM_LINK_SIZE
+            })
+            .sum::<usize>()
+}
+
+/// Offset of the first child image, right after the manifest data.
+pub fn first_image_offset(children: &[Child]) -> usize {
+    align_up(manifest_size(children), MPU_MIN_REGION_SIZE)
+}
+
+/// Serializes the manifest data. The table offsets are computed here, the image offsets must
+/// already be set.
+pub fn write(children: &[Child]) -> Result<Vec<u8>, String> {
+    if children.len() > MAX_CHILDREN {
+        return Err(format!(
+            "{} children, at most {MAX_CHILDREN} are supported",
+            children.len()
+        ));
+    }
+
+    let total_size = manifest_size(children);
+    let mut words: Vec<u32> = vec![
+        MANIFEST_MAGIC,
+        MANIFEST_VERSION,
+        total_size as u32,
+        0,
+        children.len() as u32,
+    ];
+    let mut tables: Vec<u32> = Vec::new();
+    let tables_start = HEADER_SIZE + children.len() * ENTRY_SIZE;
+
+    for child in children {
+        let name = child.name.as_bytes();
+        if name.len() > CHILD_NAME_LEN {
+            return Err(format!(
+                "{}: name longer than {CHILD_NAME_LEN} bytes",
+                child.name
+            ));
+        }
+        let mut padded = [0_u8; CHILD_NAME_LEN];
+        padded[..name.len()].copy_from_slice(name);
+        words.extend(
+            padded
+                .chunks(4)
+                .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]])),
+        );
+
+        let irq_routes_offset = tables_start + tables.len() * 4;
+        tables.extend(
+            child
+                .irq_routes
+                .iter()
+                .flat_map(|route| [route.interrupt, route.vidt_index]),
+        );
+        let shm_links_offset = tables_start + tables.len() * 4;
+        tables.extend(
+            child
+                .shm_links
+                .iter()
+                .flat_map(|link| [link.peer, link.size, link.rights, link.peer_rights]),
+        );
+
+        words.extend([
+            child.image_offset,
+            child.image_size,
+            child.rom_size,
+            child.stack_size,
+            child.extra_ram,
+            child.restart_policy as u32,
+            irq_routes_offset as u32,
+            child.irq_routes.len() as u32,
+            shm_links_offset as u32,
+            child.shm_links.len() as u32,
+        ]);
+    }
+    words.extend(tables);
+
+    words[3] = words
+        .iter()
+        .fold(0_u32, |sum, word| sum.wrapping_add(*word));
+    Ok(words.iter().flat_map(|word| word.to_le_bytes()).collect())
+}
+
+/// Parses and validates the manifest starting at the beginning of `data`.
+pub fn read(data: &[u8]) -> Result<Vec<Child>, String> {
+    let word = |offset: usize| {
+        read_word(data, offset).ok_or_else(|| format!("manifest truncated at offset {offset:#x}"))
+    };
+
+    if word(0)? != MANIFEST_MAGIC {
+        return Err("no manifest magic".to_string());
+    }
+    let version = word(4)?;
+    if version != MANIFEST_VERSION {
+        return Err(format!("unsupported manifest version {version}"));
+    }
+    let total_size = word(8)? as usize;
+    let checksum = word(12)?;
+    let child_count = word(16)? as usize;
+    if child_count > MAX_CHILDREN {
+        return Err(format!(
+            "{child_count} children, at most {MAX_CHILDREN} are supported"
+        ));
+    }
+    if !total_size.is_multiple_of(4) || total_size < HEADER_SIZE + child_count * ENTRY_SIZE {
+        return Err(format!("invalid manifest size {total_size:#x}"));
+    }
+    let sum = (0..total_size / 4)
+        .map(|i| word(i * 4))
+        .collect::<Result<Vec<_>, _>>()?
+        .iter()
+        .fold(0_u32, |sum, word| sum.wrapping_add(*word))
+        .wrapping_sub(checksum);
+    if sum != checksum {
+        return Err(format!(
+            "bad manifest checksum {checksum:#010x}, expected {sum:#010x}"
+        ));
+    }
+
+    let table = |offset: u32, count: u32, item_size: usize| -> Result<usize, String> {
+        let end = offset as usize + count as usize * item_size;
+        if end > total_size {
+            return Err(format!("table at {offset:#x} exceeds the manifest"));
+        }
+        Ok(offset as usize)
+    };
+
+    let mut children = Vec::new();
+    for index in 0..child_count {
+        let entry = HEADER_SIZE + index * ENTRY_SIZE;
+        let name_bytes = &data[entry..entry + CHILD_NAME_LEN];
+        let name_len = name_bytes
+            .iter()
+            .position(|c| *c == 0)
+            .unwrap_or(CHILD_NAME_LEN);
+        let name = String::from_utf8_lossy(&name_bytes[..name_len]).into_owned();
+        let field = |i: usize| word(entry + CHILD_NAME_LEN + i * 4);
+
+        let restart_policy = RestartPolicy::from_u32(field(5)?)
+            .ok_or_else(|| format!("{name}: unknown restart policy"))?;
+        let irq_routes_offset = table(field(6)?, field(7)?, IRQ_ROUTE_SIZE)?;
+        let irq_routes = (0..field(7)? as usize)
+            .map(|i| {
+                let route = irq_routes_offset + i * IRQ_ROUTE_SIZE;
+                Ok(IrqRoute {
+                    interrupt: word(route)?,
+                    vidt_index: word(route + 4)?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let shm_links_offset = table(field(8)?, field(9)?, SHM_LINK_SIZE)?;
+        let shm_links = (0..field(9)? as usize)
+            .map(|i| {
+                let link = shm_links_offset + i * SHM_LINK_SIZE;
+                Ok(ShmLink {
+                    peer: word(link)?,
+                    size: word(link + 4)?,
+                    rights: word(link + 8)?,
+                    peer_rights: word(link + 12)?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        children.push(Child {
+            name,
+            image_offset: field(0)?,
+            image_size: field(1)?,
+            rom_size: field(2)?,
+            stack_size: field(3)?,
+            extra_ram: field(4)?,
+            restart_policy,
+            irq_routes,
+            shm_links,
+        });
+    }
+    Ok(children)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn child(name: &str, image_offset: u32) -> Child {
+        Child {
+            name: name.to_string(),
+            image_offset,
+            image_size: 0x100,
+            rom_size: 0x120,
+            stack_size: 512,
+            extra_ram: 4096,
+            restart_policy: RestartPolicy::OnFault,
+            irq_routes: vec![IrqRoute {
+                interrupt: 17,
+                vidt_index: 1,
+            }],
+            shm_links: Vec::new(),
+        }
+    }
+
+    fn children() -> Vec<Child> {
+        let mut sensor = child("sensor", 0);
+        sensor.shm_links.push(ShmLink {
+            peer: 1,
+            size: 256,
+            rights: 0b110,
+            peer_rights: 0b100,
+        });
+        let mut radio = child("radio", 0);
+        radio.irq_routes.clear();
+        radio.restart_policy = RestartPolicy::Never;
+        let mut children = vec![sensor, radio];
+        let mut offset = first_image_offset(&children) as u32;
+        for child in &mut children {
+            child.image_offset = offset;
+            offset += child.rom_size;
+        }
+        children
+    }
+
+    fn word(data: &[u8], offset: usize) -> u32 {
+        read_word(data, offset).unwrap()
+    }
+
+    #[test]
+    fn read_back() {
+        let children = children();
+        let data = write(&children).unwrap();
+        assert_eq!(data.len(), manifest_size(&children));
+        assert_eq!(read(&data).unwrap(), children);
+    }
+
+    /// The offsets of `ManifestHeader`, `ChildEntry`, `IrqRoute` and `ShmLink` in
+    /// `pip_mpu::boot_manifest::manifest_items`.
+    #[test]
+    fn layout_matches_the_root() {
+        let children = children();
+        let data = write(&children).unwrap();
+
+        assert_eq!(word(&data, 0), MANIFEST_MAGIC);
+        assert_eq!(word(&data, 4), MANIFEST_VERSION);
+        assert_eq!(word(&data, 8) as usize, data.len());
+        assert_eq!(word(&data, 16), 2);
+        let sum = (0..data.len() / 4)
+            .filter(|i| *i != 3)
+            .fold(0_u32, |sum, i| sum.wrapping_add(word(&data, i * 4)));
+        assert_eq!(word(&data, 12), sum);
+
+        let tables = HEADER_SIZE + 2 * ENTRY_SIZE;
+        let sensor = HEADER_SIZE;
+        assert_eq!(&data[sensor..sensor + 7], b"sensor\0");
+        assert_eq!(word(&data, sensor + 16), children[0].image_offset);
+        assert_eq!(word(&data, sensor + 20), 0x100);
+        assert_eq!(word(&data, sensor + 24), 0x120);
+        assert_eq!(word(&data, sensor + 28), 512);
+        assert_eq!(word(&data, sensor + 32), 4096);
+        assert_eq!(word(&data, sensor + 36), RestartPolicy::OnFault as u32);
+        assert_eq!(word(&data, sensor + 40) as usize, tables);
+        assert_eq!(word(&data, sensor + 44), 1);
+        assert_eq!(word(&data, sensor + 48) as usize, tables + IRQ_ROUTE_SIZE);
+        assert_eq!(word(&data, sensor + 52), 1);
+        assert_eq!([word(&data, tables), word(&data, tables + 4)], [17, 1]);
+        let link = tables + IRQ_ROUTE_SIZE;
+        assert_eq!(
+            [0, 4, 8, 12].map(|field| word(&data, link + field)),
+            [1, 256, 0b110, 0b100]
+        );
+
+        let radio = HEADER_SIZE + ENTRY_SIZE;
+        assert_eq!(
+            &data[radio..radio + CHILD_NAME_LEN],
+            b"radio\0\0\0\0\0\0\0\0\0\0\0"
+        );
+        assert_eq!(word(&data, radio + 44), 0);
+        assert_eq!(word(&data, radio + 52), 0);
+    }
+
+    #[test]
+    fn first_image_is_aligned() {
+        let children = children();
+        assert!(first_image_offset(&children) >= manifest_size(&children));
+        assert!(first_image_offset(&children).is_multiple_of(MPU_MIN_REGION_SIZE));
+    }
+
+    #[test]
+    fn corrupted_manifest_is_refused() {
+        let mut data = write(&children()).unwrap();
+        data[HEADER_SIZE + 20] ^= 1;
+        assert!(read(&data).unwrap_err().contains("checksum"));
+
+        let mut data = write(&children()).unwrap();
+        data[4] = 2;
+        assert!(read(&data).is_err());
+
+        let data = write(&children()).unwrap();
+        assert!(read(&data[..data.len() - 4]).is_err());
+    }
+
+    #[test]
+    fn limits() {
+        let many: Vec<_> = (0..=MAX_CHILDREN)
+            .map(|i| child(&format!("child{i}"), 0))
+            .collect();
+        assert!(write(&many).is_err());
+        assert!(write(&[child("a_name_of_17_char", 0)]).is_err());
+    }
+
+    #[test]
+    fn rights() {
+        assert_eq!(pack_rights("r", false), Ok(0b100));
+        assert_eq!(pack_rights("rw", false), Ok(0b110));
+        assert_eq!(pack_rights("rx", false), Ok(0b101));
+        assert!(pack_rights("wx", false).is_err());
+        assert_eq!(pack_rights("rwx", true), Ok(0b111 | ALLOW_WRITE_EXEC));
+        assert!(pack_rights("rq", false).is_err());
+        assert_eq!(unpack_rights(0b101), "r-x");
+        assert_eq!(unpack_rights(0b011 | ALLOW_WRITE_EXEC), "-wx (W^X off)");
+    }
+}
@@ -0,0 +1,435 @@
+//! A global allocator over the free RAM of a partition.
+//!
+//! The allocator manages a single range, usually the RAM between `Interface::unused_ram_start`
+//! and `Interface::ram_end` the partition did not hand to its children yet:
+//!
+//! ```ignore
+//! #[global_allocator]
+//! static ALLOCATOR: PartitionAllocator = PartitionAllocator::new();
+//!
+//! // Root, once the children are booted
+//! ALLOCATOR.init(MemoryRange {
+//!     start: children.free_ram_start as usize,
+//!     size: interface.ram_end as usize - children.free_ram_start as usize,
+//! });
+//! // Child
+//! ALLOCATOR.init(child::heap());
+//! ```
+//!
+//! Free memory is kept in a list of free blocks sorted by address, merged with their
+//! neighbours on deallocation. A region can be taken out of the allocator with
+//! [`PartitionAllocator::release_region`], to be cut and given to a new child, and given back
+//! with [`PartitionAllocator::reclaim_region`] once the child is deleted.
+
+use crate::board::MemoryRange;
+use crate::tools::SpinLock;
+use core::alloc::{GlobalAlloc, Layout};
+use core::mem;
+use core::ptr;
+
+/// A free block, written at the start of the free memory it describes.
+struct FreeBlock {
+    size: usize,
+    next: *mut FreeBlock,
+}
+
+/// Every block handed out or kept free is a multiple of this unit, and aligned on it, so that
+/// any leftover can hold a `FreeBlock`.
+const UNIT: usize = mem::size_of::<FreeBlock>();
+const _: () = assert!(mem::align_of::<FreeBlock>() <= UNIT);
+
+fn align_up(x: usize, align: usize) -> usize {
+    (x + align - 1) & !(align - 1)
+}
+
+fn align_down(x: usize, align: usize) -> usize {
+    x & !(align - 1)
+}
+
+/// Usage statistics of a `PartitionAllocator`, in bytes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AllocatorStats {
+    pub total: usize, //The memory managed by the allocator, released regions excluded.
+    pub used: usize,  //The memory currently allocated.
+    pub free: usize,  //The memory available for allocations.
+    pub largest_free: usize, //The largest allocation that could succeed, alignment aside.
+    pub allocations: usize, //The number of live allocations.
+    pub released: usize, //The memory released with `release_region` and not reclaimed.
+}
+
+struct Heap {
+    head: *mut FreeBlock,
+    stats: AllocatorStats,
+}
+
+impl Heap {
+    /// Inserts `[addr, addr + size)` in the free list, merging it with its neighbours.
+    unsafe fn insert(&mut self, addr: usize, size: usize) {
+        let mut prev: *mut FreeBlock = ptr::null_mut();
+        let mut next = self.head;
+        while !next.is_null() && (next as usize) < addr {
+            prev = next;
+            next = (*next).next;
+        }
+
+        let block = addr as *mut FreeBlock;
+        block.write(FreeBlock { size, next });
+        if !next.is_null() && addr + size == next as usize {
+            (*block).size += (*next).size;
+            (*block).next = (*next).next;
+        }
+        if prev.is_null() {
+            self.head = block;
+        } else if prev as usize + (*prev).size == addr {
+            (*prev).size += (*block).size;
+            (*prev).next = (*block).next;
+        } else {
+            (*prev).next = block;
+        }
+    }
+
+    /// Takes `size` bytes aligned on `align` out of the free list, from the first block that
+    /// can hold them, or from the last one if `from_top`.
+    unsafe fn take(&mut self, size: usize, align: usize, from_top: bool) -> Option<usize> {
+        let mut found: Option<(*mut FreeBlock, *mut FreeBlock, usize)> = None;
+        let mut prev: *mut FreeBlock = ptr::null_mut();
+        let mut block = self.head;
+        while !block.is_null() {
+            let start = block as usize;
+            let end = start + (*block).size;
+            let addr = if from_top {
+                end.checked_sub(size).map(|addr| align_down(addr, align))
+            } else {
+                Some(align_up(start, align))
+            };
+            if let Some(addr) = addr.filter(|addr| *addr >= start && *addr + size <= end) {
+                found = Some((prev, block, addr));
+                if !from_top {
+                    break;
+                }
+            }
+            prev = block;
+            block = (*block).next;
+        }
+
+        let (prev, block, addr) = found?;
+        let start = block as usize;
+        let end = start + (*block).size;
+        let next = (*block).next;
+        if prev.is_null() {
+            self.head = next;
+        } else {
+            (*prev).next = next;
+        }
+        if addr > start {
+            self.insert(start, addr - start);
+        }
+        if addr + size < end {
+            self.insert(addr + size, end - addr - size);
+        }
+        Some(addr)
+    }
+
+    fn stats(&self) -> AllocatorStats {
+        let mut stats = self.stats;
+        let mut block = self.head;
+        while !block.is_null() {
+            unsafe {
+                stats.free += (*block).size;
+                stats.largest_free = stats.largest_free.max((*block).size);
+                block = (*block).next;
+            }
+        }
+        stats
+    }
+}
+
+// The free list only points into the RAM the heap was given.
+unsafe impl Send for Heap {}
+
+pub struct PartitionAllocator {
+    heap: SpinLock<Heap>,
+}
+
+impl PartitionAllocator {
+    pub const fn new() -> Self {
+        Self {
+            heap: SpinLock::new(Heap {
+                head: ptr::null_mut(),
+                stats: AllocatorStats {
+                    total: 0,
+                    used: 0,
+                    free: 0,
+                    largest_free: 0,
+                    allocations: 0,
+                    released: 0,
+                },
+            }),
+        }
+    }
+
+    /// Gives `range` to the allocator. The range must be free RAM of the partition that nothing
+    /// else uses from now on.
+    pub fn init(&self, range: MemoryRange) {
+        self.reclaim_region(range);
+    }
+
+    /// Takes `size` bytes aligned on `align` out of the allocator, from its highest free
+    /// addresses so that the rest of the heap stays contiguous.
+    ///
+    /// The region can then be cut as a block and given to a child, `size` and `align` being a
+    /// power of two for an MPU region.
+    pub fn release_region(&self, size: usize, align: usize) -> Option<MemoryRange> {
+        let size = align_up(size, UNIT);
+        let align = align.max(UNIT);
+        self.heap.lock(|heap| {
+            let start = unsafe { heap.take(size, align, true)? };
+            heap.stats.total -= size;
+            heap.stats.released += size;
+            Some(MemoryRange { start, size })
+        })
+    }
+
+    /// Gives `range` back to the allocator, e.g. the RAM of a deleted child.
+    pub fn reclaim_region(&self, range: MemoryRange) {
+        let start = align_up(range.start, UNIT);
+        let end = align_down(range.end(), UNIT);
+        if end <= start {
+            return;
+        }
+        self.heap.lock(|heap| {
+            unsafe { heap.insert(start, end - start) };
+            heap.stats.total += end - start;
+            heap.stats.released = heap.stats.released.saturating_sub(end - start);
+        })
+    }
+
+    pub fn stats(&self) -> AllocatorStats {
+        self.heap.lock(|heap| heap.stats())
+    }
+}
+
+impl Default for PartitionAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl GlobalAlloc for PartitionAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let size = align_up(layout.size().max(1), UNIT);
+        let align = layout.align().max(UNIT);
+        self.heap.lock(|heap| match heap.take(size, align, false) {
+            Some(addr) => {
+                heap.stats.used += size;
+                heap.stats.allocations += 1;
+                addr as *mut u8
+            }
+            None => ptr::null_mut(),
+        })
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let size = align_up(layout.size().max(1), UNIT);
+        self.heap.lock(|heap| {
+            heap.insert(ptr as usize, size);
+            heap.stats.used -= size;
+            heap.stats.allocations -= 1;
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// RAM for a heap, aligned so that offsets within it keep their alignment.
+    #[repr(C, align(1024))]
+    struct Arena([u8; 1024]);
+
+    impl Arena {
+        fn new() -> Box<Arena> {
+            Box::new(Arena([0; 1024]))
+        }
+
+        fn base(&self) -> usize {
+            self.0.as_ptr() as usize
+        }
+
+        fn range(&self, offset: usize, size: usize) -> MemoryRange {
+            MemoryRange {
+                start: self.base() + offset,
+                size,
+            }
+        }
+    }
+
+    fn empty_heap() -> Heap {
+        Heap {
+            head: ptr::null_mut(),
+            stats: AllocatorStats::default(),
+        }
+    }
+
+    /// The free blocks of `heap`, as offsets and sizes within `arena`.
+    fn free_list(heap: &Heap, arena: &Arena) -> Vec<(usize, usize)> {
+        let mut blocks = Vec::new();
+        let mut block = heap.head;
+        while !block.is_null() {
+            unsafe {
+                blocks.push((block as usize - arena.base(), (*block).size));
+                block = (*block).next;
+            }
+        }
+        blocks
+    }
+
+    #[test]
+    fn insert_keeps_apart_blocks_sorted() {
+        let arena = Arena::new();
+        let mut heap = empty_heap();
+        unsafe {
+            heap.insert(arena.base() + 512, 64);
+            heap.insert(arena.base(), 64);
+            heap.insert(arena.base() + 256, 64);
+        }
+        assert_eq!(free_list(&heap, &arena), [(0, 64), (256, 64), (512, 64)]);
+    }
+
+    #[test]
+    fn insert_merges_with_the_block_before() {
+        let arena = Arena::new();
+        let mut heap = empty_heap();
+        unsafe {
+            heap.insert(arena.base() + 64, 64);
+            heap.insert(arena.base() + 512, 64);
+            heap.insert(arena.base() + 128, 64);
+        }
+        assert_eq!(free_list(&heap, &arena), [(64, 128), (512, 64)]);
+    }
+
+    #[test]
+    fn insert_merges_with_the_block_after() {
+        let arena = Arena::new();
+        let mut heap = empty_heap();
+        unsafe {
+            heap.insert(arena.base() + 128, 64);
+            heap.insert(arena.base() + 64, 64);
+        }
+        assert_eq!(free_list(&heap, &arena), [(64, 128)]);
+    }
+
+    #[test]
+    fn insert_merges_both_sides() {
+        let arena = Arena::new();
+        let mut heap = empty_heap();
+        unsafe {
+            heap.insert(arena.base(), 64);
+            heap.insert(arena.base() + 128, 64);
+            heap.insert(arena.base() + 64, 64);
+        }
+        assert_eq!(free_list(&heap, &arena), [(0, 192)]);
+    }
+
+    #[test]
+    fn take_splits_the_first_fitting_block() {
+        let arena = Arena::new();
+        let mut heap = empty_heap();
+        unsafe {
+            heap.insert(arena.base(), 64);
+            heap.insert(arena.base() + 256, 256);
+            assert_eq!(heap.take(128, 64, false), Some(arena.base() + 256));
+        }
+        assert_eq!(free_list(&heap, &arena), [(0, 64), (384, 128)]);
+    }
+
+    #[test]
+    fn take_splits_around_an_aligned_address() {
+        let arena = Arena::new();
+        let mut heap = empty_heap();
+        unsafe {
+            heap.insert(arena.base() + 64, 384);
+            assert_eq!(heap.take(64, 256, false), Some(arena.base() + 256));
+        }
+        assert_eq!(free_list(&heap, &arena), [(64, 192), (320, 128)]);
+    }
+
+    #[test]
+    fn take_from_top_uses_the_last_fitting_block() {
+        let arena = Arena::new();
+        let mut heap = empty_heap();
+        unsafe {
+            heap.insert(arena.base(), 256);
+            heap.insert(arena.base() + 512, 256);
+            heap.insert(arena.base() + 896, 64);
+            assert_eq!(heap.take(128, 128, true), Some(arena.base() + 640));
+        }
+        assert_eq!(free_list(&heap, &arena), [(0, 256), (512, 128), (896, 64)]);
+    }
+
+    #[test]
+    fn take_without_room_leaves_the_list() {
+        let arena = Arena::new();
+        let mut heap = empty_heap();
+        unsafe {
+            heap.insert(arena.base() + 64, 128);
+            assert_eq!(heap.take(256, 64, false), None);
+            assert_eq!(heap.take(128, 128, false), None);
+            assert_eq!(heap.take(128, 128, true), None);
+        }
+        assert_eq!(free_list(&heap, &arena), [(64, 128)]);
+    }
+
+    #[test]
+    fn release_and_reclaim_region() {
+        let arena = Arena::new();
+        let allocator = PartitionAllocator::new();
+        allocator.init(arena.range(0, 1024));
+
+        let region = allocator.release_region(256, 256).unwrap();
+        assert_eq!(region.start, arena.base() + 768);
+        assert_eq!(region.size, 256);
+        let stats = allocator.stats();
+        assert_eq!((stats.total, stats.released, stats.free), (768, 256, 768));
+
+        assert!(allocator.release_region(1024, 1024).is_none());
+
+        allocator.reclaim_region(region);
+        let stats = allocator.stats();
+        assert_eq!((stats.total, stats.released), (1024, 0));
+        assert_eq!(stats.largest_free, 1024);
+    }
+
+    #[test]
+    fn reclaim_region_trims_to_the_unit() {
+        let arena = Arena::new();
+        let allocator = PartitionAllocator::new();
+        allocator.reclaim_region(arena.range(1, UNIT));
+        assert_eq!(allocator.stats().total, 0);
+        allocator.reclaim_region(arena.range(1, 3 * UNIT));
+        assert_eq!(allocator.stats().total, 2 * UNIT);
+    }
+
+    #[test]
+    fn alloc_and_dealloc() {
+        let arena = Arena::new();
+        let allocator = PartitionAllocator::new();
+        allocator.init(arena.range(0, 1024));
+
+        unsafe {
+            let small = allocator.alloc(Layout::from_size_align(1, 1).unwrap());
+            let aligned = allocator.alloc(Layout::from_size_align(100, 256).unwrap());
+            assert_eq!(small as usize, arena.base());
+            assert_eq!(aligned as usize, arena.base() + 256);
+            let stats = allocator.stats();
+            assert_eq!(stats.allocations, 2);
+            assert_eq!(stats.used, UNIT + align_up(100, UNIT));
+
+            allocator.dealloc(small, Layout::from_size_align(1, 1).unwrap());
+            allocator.dealloc(aligned, Layout::from_size_align(100, 256).unwrap());
+        }
+        let stats = allocator.stats();
+        assert_eq!((stats.allocations, stats.used), (0, 0));
+        assert_eq!(stats.largest_free, 1024);
+    }
+}
@@ -0,0 +1,23 @@
+//! The Decawave DWM1001 module, built around a Nordic nRF52832 (Cortex-M4F).
+
+use crate::board::specs::DWM1001;
+use crate::board::{Board, MemoryRange};
+use crate::core::pip_items::BasicFrame;
+
+pub struct Dwm1001;
+
+impl Board for Dwm1001 {
+    const NAME: &'static str = DWM1001.name;
+    const INTERRUPT_NUMBER: usize = DWM1001.interrupt_number;
+    const VIDT_SIZE: usize = DWM1001.vidt_size;
+    const MPU_REGION_COUNT: usize = DWM1001.mpu_region_count;
+    const ROM: MemoryRange = MemoryRange {
+        start: DWM1001.rom_start,
+        size: DWM1001.rom_size,
+    };
+    const RAM: MemoryRange = MemoryRange {
+        start: DWM1001.ram_start,
+        size: DWM1001.ram_size,
+    };
+    type Frame = BasicFrame;
+}
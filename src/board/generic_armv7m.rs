@@ -0,0 +1,26 @@
+//! Any ARMv7-M chip (Cortex-M3/M4/M7) pip has been ported to.
+//!
+//! Only the architectural guarantees are assumed : 8 MPU regions, at least 32 external
+//! interrupts, and the code and SRAM regions of the ARMv7-M memory map.
+
+use crate::board::specs::GENERIC_ARMV7M;
+use crate::board::{Board, MemoryRange};
+use crate::core::pip_items::BasicFrame;
+
+pub struct GenericArmv7m;
+
+impl Board for GenericArmv7m {
+    const NAME: &'static str = GENERIC_ARMV7M.name;
+    const INTERRUPT_NUMBER: usize = GENERIC_ARMV7M.interrupt_number;
+    const VIDT_SIZE: usize = GENERIC_ARMV7M.vidt_size;
+    const MPU_REGION_COUNT: usize = GENERIC_ARMV7M.mpu_region_count;
+    const ROM: MemoryRange = MemoryRange {
+        start: GENERIC_ARMV7M.rom_start,
+        size: GENERIC_ARMV7M.rom_size,
+    };
+    const RAM: MemoryRange = MemoryRange {
+        start: GENERIC_ARMV7M.ram_start,
+        size: GENERIC_ARMV7M.ram_size,
+    };
+    type Frame = BasicFrame;
+}
@@ -0,0 +1,70 @@
+//! The hardware the partitions run on.
+//!
+//! Exactly one board is selected through the cargo features of the crate, `dwm1001` being the
+//! default one. `CurrentBoard` is the selected board, the rest of the crate only relies on it.
+//! The figures of every board are written in `specs`, which `build.rs` reads as well.
+
+use crate::core::pip_items::Frame;
+
+pub mod specs;
+
+#[cfg(feature = "dwm1001")]
+pub mod dwm1001;
+#[cfg(feature = "generic-armv7m")]
+pub mod generic_armv7m;
+
+#[cfg(all(feature = "dwm1001", feature = "generic-armv7m"))]
+compile_error!("the dwm1001 and generic-armv7m features select different boards, enable only one");
+
+#[cfg(not(any(feature = "dwm1001", feature = "generic-armv7m")))]
+compile_error!("no board selected, enable one of the dwm1001 or generic-armv7m features");
+
+#[cfg(feature = "dwm1001")]
+pub type CurrentBoard = dwm1001::Dwm1001;
+#[cfg(all(feature = "generic-armv7m", not(feature = "dwm1001")))]
+pub type CurrentBoard = generic_armv7m::GenericArmv7m;
+
+/// The frame saved in the contexts of the partitions on the selected board.
+pub type BoardFrame = <CurrentBoard as Board>::Frame;
+
+/// A contiguous range of the address space.
+#[derive(Clone, Copy, Debug)]
+pub struct MemoryRange {
+    pub start: usize, //The first address of the range.
+    pub size: usize,  //The size of the range in bytes.
+}
+
+impl MemoryRange {
+    pub const fn end(&self) -> usize {
+        self.start + self.size
+    }
+
+    pub const fn contains(&self, addr: usize) -> bool {
+        addr >= self.start && addr < self.end()
+    }
+}
+
+/// What the partition manager needs to know about an ARMv7-M chip and the pip port running on it.
+pub trait Board {
+    /// The name of the board, as written in `partitions.toml` errors and logs.
+    const NAME: &'static str;
+
+    /// The number of entries of the VIDT, i.e. the 16 system exceptions plus the external
+    /// interrupts of the chip.
+    const INTERRUPT_NUMBER: usize;
+
+    /// The size pip reserves for the VIDT and its interrupt stack.
+    const VIDT_SIZE: usize;
+
+    /// The number of regions of the MPU.
+    const MPU_REGION_COUNT: usize;
+
+    /// The flash of the chip.
+    const ROM: MemoryRange;
+
+    /// The SRAM of the chip.
+    const RAM: MemoryRange;
+
+    /// The frame saved by pip in the context of a partition.
+    type Frame: Frame + Default;
+}
@@ -0,0 +1,53 @@
+//! The figures of the boards, of the MPU and of pip, written once.
+//!
+//! This file uses nothing but `core`: `build.rs` and `relocation_tools/pip-image` include it with
+//! `#[path]` to check `partitions.toml` and the images against the same figures as the crate.
+
+/// What a board is made of, the [`Board`](crate::board::Board) implementations being read from it.
+pub struct BoardSpec {
+    pub name: &'static str,      //The board feature of Cargo.toml.
+    pub interrupt_number: usize, //The 16 system exceptions plus the external interrupts.
+    pub vidt_size: usize,        //The size pip reserves for the VIDT and its interrupt stack.
+    pub mpu_region_count: usize,
+    pub rom_start: usize,
+    pub rom_size: usize,
+    pub ram_start: usize,
+    pub ram_size: usize,
+}
+
+/// The Decawave DWM1001 module, built around a Nordic nRF52832 (Cortex-M4F).
+pub const DWM1001: BoardSpec = BoardSpec {
+    name: "dwm1001",
+    interrupt_number: 54, // 16 system exceptions and 38 nRF52832 interrupts.
+    vidt_size: 512,
+    mpu_region_count: 8,
+    rom_start: 0x0000_0000,
+    rom_size: 0x8_0000, // 512 KiB
+    ram_start: 0x2000_0000,
+    ram_size: 0x1_0000, // 64 KiB
+};
+
+/// Any ARMv7-M chip, only the architectural guarantees being assumed.
+pub const GENERIC_ARMV7M: BoardSpec = BoardSpec {
+    name: "generic-armv7m",
+    interrupt_number: 48, // 16 system exceptions and 32 external interrupts.
+    vidt_size: 256,
+    mpu_region_count: 8,
+    rom_start: 0x0000_0000,
+    rom_size: 0x2000_0000, // The code region.
+    ram_start: 0x2000_0000,
+    ram_size: 0x2000_0000, // The SRAM region.
+};
+
+pub const BOARDS: [BoardSpec; 2] = [DWM1001, GENERIC_ARMV7M];
+
+/// The smallest block an ARMv7-M MPU region can cover, and the alignment of every block.
+pub const MPU_MIN_REGION_SIZE: usize = 32;
+
+/// `m_map_partition` uses the MPU regions 0 to 4 of every partition, shared blocks are mapped in
+/// the remaining ones.
+pub const PARTITION_MPU_REGIONS: usize = 5;
+
+/// Pip needs a partition descriptor and a kernel structure for the child, plus a new kernel
+/// structure for the parent, each 512 bytes long and aligned on 512 bytes.
+pub const PIP_BLOCKS_SIZE: usize = 2048;
@@ -0,0 +1,418 @@
+use crate::board::specs::{MPU_MIN_REGION_SIZE, PARTITION_MPU_REGIONS, PIP_BLOCKS_SIZE};
+use crate::boot_manifest::manifest_items::{ChildDesc, Manifest, ManifestError, MAX_CHILDREN};
+use crate::config;
+use crate::core::pip_items::{ContextKind, ExtendedContext, Interface};
+use crate::manage_partition::manage_partition::{
+    child_kern_addr, ctx_itf_block_size, m_create_partition, m_delete_partition, m_map_partition,
+};
+use crate::manage_partition::partition_items::CreateReturn;
+use crate::rust::pip_rust_items::{
+    AccessRights, AddedBlockId, Block, KernStructId, MemBlockId, MpuRegion, PartDescId,
+};
+use crate::rust::pip_rust_mpu;
+use crate::tools;
+use core::fmt;
+use ptr_bits_ops::PtrBitsOps;
+
+/// Shared memory is mapped in the MPU regions `m_map_partition` leaves free.
+const FIRST_SHM_MPU_REGION: usize = PARTITION_MPU_REGIONS;
+const MAX_SHM_PER_CHILD: usize = config::MPU_REGION_COUNT - FIRST_SHM_MPU_REGION;
+
+/// The manifest has no per child frame, the children it describes must not use the FPU.
+const CHILD_CONTEXT_KIND: ContextKind = ContextKind::BASIC;
+
+#[derive(Debug)]
+pub enum BootError {
+    Manifest(ManifestError),
+    NoRam(usize),               //Not enough free RAM left for the child at this index.
+    CreateFailed(usize),        //Pip refused to create the child at this index.
+    ShareFailed(usize), //A shared memory block of the child at this index could not be set up.
+    MpuRegionsExhausted(usize), //The child at this index shares more blocks than it has free MPU regions.
+}
+
+impl fmt::Display for BootError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BootError::Manifest(error) => write!(f, "{error}"),
+            BootError::NoRam(index) => write!(f, "not enough free RAM for child {index}"),
+            BootError::CreateFailed(index) => write!(f, "pip refused to create child {index}"),
+            BootError::ShareFailed(index) => {
+                write!(f, "a shared block of child {index} could not be set up")
+            }
+            BootError::MpuRegionsExhausted(index) => {
+                write!(
+                    f,
+                    "child {index} shares more blocks than it has free MPU regions"
+                )
+            }
+        }
+    }
+}
+
+/// A shared memory block, as given to one of the children.
+pub struct SharedBlock {
+    pub block_id: AddedBlockId, //The shared block within the root, and its local id within the child
+    pub rights: AccessRights,
+    pub mpu_region: MpuRegion,
+}
+
+/// A child brought up from the manifest.
+pub struct BootedChild {
+    pub desc: ChildDesc,
+    pub partition: CreateReturn,
+    pub ram_start: *const u32, //Start of the RAM block the child was created in, used to recreate it
+    pub kern_start: *const u8, //Start of the child's kernel structure and partition descriptor, cut at the end of its RAM block
+    pub shared: [Option<SharedBlock>; MAX_SHM_PER_CHILD],
+}
+
+impl BootedChild {
+    fn next_shm_slot(&self) -> Option<usize> {
+        self.shared.iter().position(|slot| slot.is_none())
+    }
+}
+
+pub struct BootedChildren {
+    pub children: [Option<BootedChild>; MAX_CHILDREN],
+    pub count: usize,
+    pub free_ram_start: *const u8, //Start of the root's free RAM once every child has been booted
+}
+
+impl BootedChildren {
+    pub fn get(&self, index: usize) -> Option<&BootedChild> {
+        self.children.get(index).and_then(|child| child.as_ref())
+    }
+
+    /// Whether `block_id`, a local id within the root, was given to or is shared with a child.
+    pub fn owns(&self, block_id: &MemBlockId) -> bool {
+        self.children.iter().flatten().any(|child| {
+            child.partition.owns(block_id)
+                || child
+                    .shared
+                    .iter()
+                    .flatten()
+                    .any(|shared| shared.block_id.block_id().id() == block_id.id())
+        })
+    }
+
+    /// The start addresses of the blocks the root cut for its children: the RAM block of each
+    /// child, its pip structures, its ROM from its entry point, and the free RAM left. Unused
+    /// entries are 0.
+    pub fn block_starts(&self) -> [usize; 3 * MAX_CHILDREN + 1] {
+        let mut starts = [0; 3 * MAX_CHILDREN + 1];
+        for (index, child) in self.children.iter().enumerate() {
+            if let Some(child) = child {
+                starts[3 * index] = child.ram_start as usize;
+                starts[3 * index + 1] = child.kern_start as usize;
+                starts[3 * index + 2] = child.desc.entry_point() as usize;
+            }
+        }
+        starts[3 * MAX_CHILDREN] = self.free_ram_start as usize;
+        starts
+    }
+
+    /// Finds the child an interrupt is routed to, with the index of its VIDT to yield to.
+    pub fn route_of(&self, interrupt: u32) -> Option<(&BootedChild, u32)> {
+        self.children.iter().flatten().find_map(|child| {
+            child
+                .desc
+                .irq_routes()
+                .iter()
+                .find(|route| route.interrupt == interrupt)
+                .map(|route| (child, route.vidt_index))
+        })
+    }
+}
+
+/// The RAM needed by a child whose block starts at `start`, including the alignment of its
+/// stack/vidt block and `pip_blocks_size` bytes for the pip structures cut at its end.
+fn child_ram_size(
+    start: *const u8,
+    stack_size: usize,
+    extra_ram: usize,
+    pip_blocks_size: usize,
+) -> usize {
+    let stack_vidt_block_size =
+        tools::next_pow_of_2((stack_size + config::VIDT_SIZE).try_into().unwrap()) as usize;
+    let ctx_itf_block_size = ctx_itf_block_size(CHILD_CONTEXT_KIND);
+
+    let unused_ram_addr = start
+        .bits_align(stack_vidt_block_size)
+        .add_bits_offset(stack_vidt_block_size)
+        .bits_align(MPU_MIN_REGION_SIZE)
+        .add_bits_offset(ctx_itf_block_size)
+        .bits_align(MPU_MIN_REGION_SIZE);
+    let used = unused_ram_addr as usize - start as usize;
+
+    (used + extra_ram).next_multiple_of(MPU_MIN_REGION_SIZE) + pip_blocks_size
+}
+
+/// Cuts `size` bytes out of the root's free RAM, starting at the first address aligned on
+/// `align` from `cursor`, without going past `limit`.
+///
+/// Returns the cut block, and the address from which the root's free RAM now starts.
+fn cut_free_ram(
+    root_pd_block_id: &PartDescId,
+    cursor: *const u8,
+    size: usize,
+    align: usize,
+    limit: *const u8,
+) -> Result<(Block, *const u8), ()> {
+    let start = cursor.bits_align(align);
+    let free = pip_rust_mpu::find_block(root_pd_block_id, cursor as *const u32)?;
+    let end = start.add_bits_offset(size);
+    let free_end = free.end() as *const u8;
+    if end > free_end || end > limit {
+        return Err(());
+    }
+
+    let block_id = if start == free.start_addr as *const u8 {
+        free.local_id
+    } else {
+        pip_rust_mpu::cut_memory_block(free.local_id, start as *const u32, None)
+            .map_err(|_| ())?
+            .1
+    };
+    if end < free_end {
+        pip_rust_mpu::cut_memory_block(block_id, end as *const u32, None).map_err(|_| ())?;
+    }
+
+    Ok((
+        pip_rust_mpu::find_block(root_pd_block_id, start as *const u32)?,
+        end,
+    ))
+}
+
+fn create_child(
+    interface: &Interface,
+    root_ctx: *const ExtendedContext,
+    child_ram_block: Block,
+    pip_block: Option<Block>,
+    parent_kern_block_id: Option<KernStructId>,
+    desc: &ChildDesc,
+) -> Result<CreateReturn, ()> {
+    let partition = m_create_partition(
+        interface,
+        root_ctx,
+        child_ram_block,
+        pip_block,
+        parent_kern_block_id,
+        desc.entry_point(),
+        desc.stack_size(),
+        config::VIDT_SIZE,
+        CHILD_CONTEXT_KIND,
+        desc.used_rom_size(),
+        desc.unused_rom_size(),
+    )?;
+    m_map_partition(&partition);
+    Ok(partition)
+}
+
+/// Adds the root's `block_id` to `child` and maps it in the child's next free MPU region.
+fn share_with(
+    child: &mut BootedChild,
+    block_id: MemBlockId,
+    rights: AccessRights,
+) -> Result<(), ()> {
+    let slot = child.next_shm_slot().ok_or(())?;
+    let mpu_region = MpuRegion::new(FIRST_SHM_MPU_REGION + slot).ok_or(())?;
+    let pd_block_id = &child.partition.parent_infos.pd_block_id;
+
+    let block_id = pip_rust_mpu::add_memory_block(pd_block_id, block_id, rights).map_err(|_| ())?;
+    pip_rust_mpu::map_mpu(pd_block_id, block_id.child_block_id(), mpu_region)?;
+
+    child.shared[slot] = Some(SharedBlock {
+        block_id,
+        rights,
+        mpu_region,
+    });
+    Ok(())
+}
+
+/// Brings up every child described by `manifest`, in order.
+///
+/// Each child gets its own RAM block, cut from the root's free RAM starting at
+/// `interface.unused_ram_start`, and its ROM is the image stored in the manifest.
+/// The children and their shared blocks take at most `config::RAM_SIZE` bytes from there, the
+/// RAM `build.rs` checked they fit in.
+/// Once every child exists, the shared memory links are set up : each shared block is cut from
+/// the remaining free RAM, added to both children and mapped in their MPU after the regions used
+/// by `m_map_partition`.
+///
+/// `root_ctx` is where the root's context is saved when yielding to a child.
+pub fn m_boot_children(
+    interface: &Interface,
+    root_ctx: *const ExtendedContext,
+    manifest: &Manifest,
+) -> Result<BootedChildren, BootError> {
+    let root_pd_block_id = PartDescId::current(interface);
+    let mut booted = BootedChildren {
+        children: core::array::from_fn(|_| None),
+        count: 0,
+        free_ram_start: (interface.unused_ram_start as *const u8).bits_align(MPU_MIN_REGION_SIZE),
+    };
+
+    let ram_end = booted.free_ram_start.add_bits_offset(config::RAM_SIZE);
+
+    for index in 0..manifest.child_count() {
+        let desc = manifest.child(index).unwrap();
+        let size = child_ram_size(
+            booted.free_ram_start,
+            desc.stack_size(),
+            desc.extra_ram(),
+            PIP_BLOCKS_SIZE,
+        );
+
+        let (child_ram_block, free_ram_start) = cut_free_ram(
+            &root_pd_block_id,
+            booted.free_ram_start,
+            size,
+            MPU_MIN_REGION_SIZE,
+            ram_end,
+        )
+        .map_err(|_| BootError::NoRam(index))?;
+        booted.free_ram_start = free_ram_start;
+
+        let ram_start = child_ram_block.start_addr;
+        let kern_start = child_kern_addr(&child_ram_block);
+        let partition = create_child(interface, root_ctx, child_ram_block, None, None, &desc)
+            .map_err(|_| BootError::CreateFailed(index))?;
+
+        booted.children[index] = Some(BootedChild {
+            desc,
+            partition,
+            ram_start,
+            kern_start,
+            shared: Default::default(),
+        });
+        booted.count += 1;
+    }
+
+    for index in 0..booted.count {
+        let desc = booted.children[index].as_ref().unwrap().desc;
+        for link in desc.shm_links() {
+            let size = tools::next_pow_of_2(link.size.max(MPU_MIN_REGION_SIZE as u32)) as usize;
+            let (shared_block, free_ram_start) = cut_free_ram(
+                &root_pd_block_id,
+                booted.free_ram_start,
+                size,
+                size,
+                ram_end,
+            )
+            .map_err(|_| BootError::NoRam(index))?;
+            booted.free_ram_start = free_ram_start;
+            let shared_start = shared_block.start_addr;
+
+            let sides = [
+                (index, link.rights()),
+                (link.peer as usize, link.peer_rights()),
+            ];
+            // Both children are checked first, so that the block is never left shared with one.
+            for (child_index, _) in sides {
+                let child = booted.children[child_index].as_ref().unwrap();
+                if child.next_shm_slot().is_none() {
+                    return Err(BootError::MpuRegionsExhausted(child_index));
+                }
+            }
+            for (child_index, rights) in sides {
+                let child = booted.children[child_index].as_mut().unwrap();
+                // Each child is given its own handle on the block, pip checks the block can be
+                // added to both.
+                let block = pip_rust_mpu::find_block(&root_pd_block_id, shared_start)
+                    .map_err(|_| BootError::ShareFailed(child_index))?;
+                share_with(child, block.local_id, rights)
+                    .map_err(|_| BootError::ShareFailed(child_index))?;
+            }
+        }
+    }
+
+    Ok(booted)
+}
+
+/// Deletes the child at `index` and brings it up again from its manifest entry, in the same RAM.
+///
+/// The kernel structure the root prepared for the child is kept between the child's RAM and its
+/// pip structures, which are then cut from their own block. The RAM block left is checked against
+/// the needs of the child before recreating it. The shared memory blocks the child had are given
+/// back to it with the same rights and MPU regions.
+///
+/// The root does not watch its children: the caller tells that a child stopped, and restarts it
+/// when `desc.restart_policy().should_restart(faulted)` holds.
+pub fn m_restart_child(
+    interface: &Interface,
+    root_ctx: *const ExtendedContext,
+    booted: &mut BootedChildren,
+    index: usize,
+) -> Result<(), BootError> {
+    let root_pd_block_id = PartDescId::current(interface);
+    let BootedChild {
+        desc,
+        partition,
+        ram_start,
+        kern_start,
+        shared,
+    } = booted.children[index]
+        .take()
+        .ok_or(BootError::CreateFailed(index))?;
+    booted.count -= 1;
+
+    let pd_block_id = &partition.parent_infos.pd_block_id;
+    let mut removed: [Option<(MemBlockId, AccessRights)>; MAX_SHM_PER_CHILD] = Default::default();
+    for (slot, shared_block) in shared.into_iter().enumerate() {
+        let Some(shared_block) = shared_block else {
+            continue;
+        };
+        pip_rust_mpu::unmap_mpu(pd_block_id, shared_block.mpu_region)
+            .map_err(|_| BootError::ShareFailed(index))?;
+        let block_id = pip_rust_mpu::remove_memory_block(shared_block.block_id)
+            .map_err(|_| BootError::ShareFailed(index))?;
+        removed[slot] = Some((block_id, shared_block.rights));
+    }
+
+    let deleted = m_delete_partition(partition);
+
+    let child_ram_block = pip_rust_mpu::find_block(&root_pd_block_id, ram_start)
+        .map_err(|_| BootError::NoRam(index))?;
+    let (pip_block, pip_blocks_size) = match deleted.pip_block_id {
+        Some(_) => (
+            Some(
+                pip_rust_mpu::find_block(&root_pd_block_id, kern_start as *const u32)
+                    .map_err(|_| BootError::NoRam(index))?,
+            ),
+            0,
+        ),
+        None => (None, PIP_BLOCKS_SIZE),
+    };
+    let size = child_ram_size(
+        child_ram_block.start_addr as *const u8,
+        desc.stack_size(),
+        desc.extra_ram(),
+        pip_blocks_size,
+    );
+    if child_ram_block.size() < size {
+        return Err(BootError::NoRam(index));
+    }
+
+    let partition = create_child(
+        interface,
+        root_ctx,
+        child_ram_block,
+        pip_block,
+        deleted.parent_kern_block_id,
+        &desc,
+    )
+    .map_err(|_| BootError::CreateFailed(index))?;
+
+    let child = booted.children[index].insert(BootedChild {
+        desc,
+        partition,
+        ram_start,
+        kern_start,
+        shared: Default::default(),
+    });
+    booted.count += 1;
+
+    for (block_id, rights) in removed.into_iter().flatten() {
+        share_with(child, block_id, rights).map_err(|_| BootError::ShareFailed(index))?;
+    }
+    Ok(())
+}
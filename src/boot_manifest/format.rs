@@ -0,0 +1,11 @@
+//! The figures of the boot manifest format, written once.
+//!
+//! This file uses nothing but `core`: `build.rs` and `relocation_tools/pip-image` include it with
+//! `#[path]`, so that the manifests written on the host are the ones the root partition reads.
+
+/// "PIPM" read as a little endian word.
+pub const MANIFEST_MAGIC: u32 = 0x4D50_4950;
+pub const MANIFEST_VERSION: u32 = 1;
+
+pub const CHILD_NAME_LEN: usize = 16;
+pub const MAX_CHILDREN: usize = 8;
@@ -0,0 +1,352 @@
+use crate::board::specs::MPU_MIN_REGION_SIZE;
+use crate::config;
+use crate::core::pip_items::Interface;
+use crate::rust::pip_rust_items::AccessRights;
+use core::fmt;
+use core::mem;
+use enum_from_derive::FromU32;
+use ptr_bits_ops::PtrBitsOps;
+
+pub use crate::boot_manifest::format::{
+    CHILD_NAME_LEN, MANIFEST_MAGIC, MANIFEST_VERSION, MAX_CHILDREN,
+};
+
+/// The manifest, as well as every child image it describes, starts on an MPU aligned address.
+pub const MANIFEST_ALIGN: usize = MPU_MIN_REGION_SIZE;
+
+/*
+    Binary layout, every field is a little endian u32 :
+
+    +----------------------------+ <- manifest start, aligned on MANIFEST_ALIGN
+    | ManifestHeader             |
+    +----------------------------+
+    | ChildEntry 0               |
+    | ...                        |
+    | ChildEntry child_count - 1 |
+    +----------------------------+
+    | IrqRoute / ShmLink tables  | -> referenced by offset from the entries
+    +----------------------------+
+    | child images               | -> referenced by offset from the entries, each aligned on MANIFEST_ALIGN
+    +----------------------------+
+*/
+
+#[repr(C)]
+pub struct ManifestHeader {
+    pub magic: u32,       //MANIFEST_MAGIC
+    pub version: u32,     //MANIFEST_VERSION
+    pub total_size: u32, //Size in bytes of the header, the entries and the tables. Child images are not included.
+    pub checksum: u32,   //Wrapping sum of every word within total_size, this field being read as 0.
+    pub child_count: u32, //Number of ChildEntry following the header.
+}
+
+#[repr(C)]
+pub struct ChildEntry {
+    pub name: [u8; CHILD_NAME_LEN], //Zero padded name of the child, used for logs only.
+    pub image_offset: u32,          //Offset of the child image from the manifest start.
+    pub image_size: u32,            //Size of the child image, i.e. its used ROM.
+    pub rom_size: u32, //ROM reserved for the child, image included. The tail becomes its unused ROM.
+    pub stack_size: u32, //The desired size of the child's stack, 0 for the root's default.
+    pub extra_ram: u32, //Unused RAM given to the child on top of its stack, vidt, context and interface.
+    pub restart_policy: u32, //A RestartPolicy.
+    pub irq_routes_offset: u32, //Offset of the child's IrqRoute table from the manifest start.
+    pub irq_routes_count: u32,
+    pub shm_links_offset: u32, //Offset of the child's ShmLink table from the manifest start.
+    pub shm_links_count: u32,
+}
+
+/// An interrupt received by the root which should be forwarded to a child.
+#[repr(C)]
+pub struct IrqRoute {
+    pub interrupt: u32,  //The interrupt number, as seen in the root's VIDT.
+    pub vidt_index: u32, //The index of the child's VIDT to yield to.
+}
+
+/// A memory block shared between the owning child and a peer child.
+#[repr(C)]
+pub struct ShmLink {
+    pub peer: u32,        //Index of the peer child within the manifest.
+    pub size: u32,        //Requested size, rounded up to the next MPU compatible power of 2.
+    pub rights: u32, //Rights of the owning child, packed as (r << 2) | (w << 1) | x, 0b1000 disabling the W^X check.
+    pub peer_rights: u32, //Rights of the peer child, same packing.
+}
+
+impl ShmLink {
+    pub fn rights(&self) -> AccessRights {
+        AccessRights::from_bits_truncate(self.rights)
+    }
+
+    pub fn peer_rights(&self) -> AccessRights {
+        AccessRights::from_bits_truncate(self.peer_rights)
+    }
+}
+
+#[derive(FromU32, Clone, Copy, PartialEq, Eq)]
+pub enum RestartPolicy {
+    NEVER = 0,
+    ALWAYS = 1,
+    ON_FAULT = 2,
+}
+
+impl RestartPolicy {
+    /// Whether a child which stopped, faulting or not, should be brought up again.
+    pub fn should_restart(&self, faulted: bool) -> bool {
+        match self {
+            RestartPolicy::NEVER => false,
+            RestartPolicy::ALWAYS => true,
+            RestartPolicy::ON_FAULT => faulted,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ManifestError {
+    NotFound,
+    UnsupportedVersion(u32),
+    BadChecksum,
+    TooManyChildren(u32),
+    OutOfBounds(usize), //The child at this index references data outside of the manifest or the ROM.
+    BadRestartPolicy(usize), //The child at this index has an unknown restart policy.
+    BadPeer(usize),     //The child at this index shares memory with an unknown peer.
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestError::NotFound => write!(f, "no boot manifest after the root image"),
+            ManifestError::UnsupportedVersion(version) => {
+                write!(f, "unsupported manifest version {version}")
+            }
+            ManifestError::BadChecksum => write!(f, "bad manifest checksum"),
+            ManifestError::TooManyChildren(count) => {
+                write!(f, "{count} children, at most {MAX_CHILDREN} are supported")
+            }
+            ManifestError::OutOfBounds(index) => {
+                write!(f, "child {index} lies outside of the manifest or the ROM")
+            }
+            ManifestError::BadRestartPolicy(index) => {
+                write!(f, "child {index} has an unknown restart policy")
+            }
+            ManifestError::BadPeer(index) => {
+                write!(f, "child {index} shares memory with an unknown peer")
+            }
+        }
+    }
+}
+
+/// A validated view over a boot manifest lying in ROM.
+#[derive(Clone, Copy)]
+pub struct Manifest {
+    header: &'static ManifestHeader,
+}
+
+impl Manifest {
+    /// Searches the partition's unused ROM for a manifest.
+    ///
+    /// The manifest is placed on the first [`MANIFEST_ALIGN`] aligned address after the root image,
+    /// which lies at or after `unused_rom_start`. Every aligned address up to `rom_end` is tried.
+    pub fn find(interface: &Interface) -> Result<Manifest, ManifestError> {
+        let mut addr = interface.unused_rom_start.bits_align(MANIFEST_ALIGN);
+        while addr.add_bits_offset(mem::size_of::<ManifestHeader>()) <= interface.rom_end {
+            if unsafe { *(addr as *const u32) } == MANIFEST_MAGIC {
+                if let Ok(manifest) = Manifest::from_addr(addr, interface.rom_end) {
+                    return Ok(manifest);
+                }
+            }
+            addr = addr.add_bits_offset(MANIFEST_ALIGN);
+        }
+        Err(ManifestError::NotFound)
+    }
+
+    /// Validates the manifest lying at `addr`, which must not exceed `rom_end`.
+    pub fn from_addr(addr: *const u8, rom_end: *const u8) -> Result<Manifest, ManifestError> {
+        if !(addr as usize).is_multiple_of(MANIFEST_ALIGN)
+            || (rom_end as usize).saturating_sub(addr as usize) < mem::size_of::<ManifestHeader>()
+        {
+            return Err(ManifestError::NotFound);
+        }
+        let header = unsafe { &*(addr as *const ManifestHeader) };
+        if header.magic != MANIFEST_MAGIC {
+            return Err(ManifestError::NotFound);
+        }
+        if header.version != MANIFEST_VERSION {
+            return Err(ManifestError::UnsupportedVersion(header.version));
+        }
+        if header.child_count as usize > MAX_CHILDREN {
+            return Err(ManifestError::TooManyChildren(header.child_count));
+        }
+
+        let total_size = header.total_size as usize;
+        // child_count is at most MAX_CHILDREN, this can not overflow.
+        let entries_end = mem::size_of::<ManifestHeader>()
+            + header.child_count as usize * mem::size_of::<ChildEntry>();
+        if total_size < entries_end
+            || !total_size.is_multiple_of(4)
+            || total_size > rom_end as usize - addr as usize
+        {
+            return Err(ManifestError::OutOfBounds(0));
+        }
+
+        let words = unsafe { core::slice::from_raw_parts(addr as *const u32, total_size / 4) };
+        let sum = words
+            .iter()
+            .fold(0_u32, |sum, word| sum.wrapping_add(*word))
+            .wrapping_sub(header.checksum);
+        if sum != header.checksum {
+            return Err(ManifestError::BadChecksum);
+        }
+
+        let manifest = Manifest { header };
+        for index in 0..manifest.child_count() {
+            manifest.check_child(index, rom_end)?;
+        }
+        Ok(manifest)
+    }
+
+    fn check_child(&self, index: usize, rom_end: *const u8) -> Result<(), ManifestError> {
+        let entry = self.entry(index);
+        let total_size = self.header.total_size as usize;
+        // The tables are read in place, they must be aligned for their items. The manifest start
+        // is aligned on MANIFEST_ALIGN, so their offsets are.
+        let table_fits = |offset: u32, count: u32, item_size: usize, item_align: usize| {
+            (offset as usize).is_multiple_of(item_align)
+                && (count as usize)
+                    .checked_mul(item_size)
+                    .and_then(|len| (offset as usize).checked_add(len))
+                    .is_some_and(|end| end <= total_size)
+        };
+        let rom_left = rom_end as usize - self.base() as usize;
+        let image_fits = (entry.image_offset as usize)
+            .checked_add(entry.rom_size as usize)
+            .is_some_and(|end| end <= rom_left);
+
+        if !table_fits(
+            entry.irq_routes_offset,
+            entry.irq_routes_count,
+            mem::size_of::<IrqRoute>(),
+            mem::align_of::<IrqRoute>(),
+        ) || !table_fits(
+            entry.shm_links_offset,
+            entry.shm_links_count,
+            mem::size_of::<ShmLink>(),
+            mem::align_of::<ShmLink>(),
+        ) || entry.image_size > entry.rom_size
+            || !(entry.image_offset as usize).is_multiple_of(MANIFEST_ALIGN)
+            || !image_fits
+        {
+            return Err(ManifestError::OutOfBounds(index));
+        }
+
+        if RestartPolicy::from_u32(entry.restart_policy).is_none() {
+            return Err(ManifestError::BadRestartPolicy(index));
+        }
+
+        let child = self.child(index).unwrap();
+        if child
+            .shm_links()
+            .iter()
+            .any(|link| link.peer as usize >= self.child_count() || link.peer as usize == index)
+        {
+            return Err(ManifestError::BadPeer(index));
+        }
+        Ok(())
+    }
+
+    pub fn base(&self) -> *const u8 {
+        self.header as *const ManifestHeader as *const u8
+    }
+
+    pub fn child_count(&self) -> usize {
+        self.header.child_count as usize
+    }
+
+    /// The child described at `index`, in boot order.
+    pub fn child(&self, index: usize) -> Option<ChildDesc> {
+        (index < self.child_count()).then(|| ChildDesc {
+            manifest: *self,
+            entry: self.entry(index),
+        })
+    }
+
+    fn entry(&self, index: usize) -> &'static ChildEntry {
+        unsafe {
+            &*(self
+                .base()
+                .add_bits_offset(mem::size_of::<ManifestHeader>())
+                .add_bits_offset(index * mem::size_of::<ChildEntry>())
+                as *const ChildEntry)
+        }
+    }
+}
+
+/// A child partition, as described by the manifest.
+#[derive(Clone, Copy)]
+pub struct ChildDesc {
+    manifest: Manifest,
+    pub entry: &'static ChildEntry,
+}
+
+impl ChildDesc {
+    pub fn name(&self) -> &'static str {
+        let len = self
+            .entry
+            .name
+            .iter()
+            .position(|c| *c == 0)
+            .unwrap_or(CHILD_NAME_LEN);
+        core::str::from_utf8(&self.entry.name[..len]).unwrap_or("?")
+    }
+
+    pub fn entry_point(&self) -> *const u8 {
+        self.manifest
+            .base()
+            .add_bits_offset(self.entry.image_offset as usize)
+    }
+
+    pub fn used_rom_size(&self) -> usize {
+        self.entry.image_size as usize
+    }
+
+    pub fn unused_rom_size(&self) -> usize {
+        (self.entry.rom_size - self.entry.image_size) as usize
+    }
+
+    /// The size of the child's stack, `config::STACK_SIZE` when its entry leaves it at 0.
+    pub fn stack_size(&self) -> usize {
+        match self.entry.stack_size {
+            0 => config::STACK_SIZE,
+            stack_size => stack_size as usize,
+        }
+    }
+
+    pub fn extra_ram(&self) -> usize {
+        self.entry.extra_ram as usize
+    }
+
+    pub fn restart_policy(&self) -> RestartPolicy {
+        RestartPolicy::from_u32(self.entry.restart_policy).unwrap()
+    }
+
+    pub fn irq_routes(&self) -> &'static [IrqRoute] {
+        unsafe {
+            core::slice::from_raw_parts(
+                self.manifest
+                    .base()
+                    .add_bits_offset(self.entry.irq_routes_offset as usize)
+                    as *const IrqRoute,
+                self.entry.irq_routes_count as usize,
+            )
+        }
+    }
+
+    pub fn shm_links(&self) -> &'static [ShmLink] {
+        unsafe {
+            core::slice::from_raw_parts(
+                self.manifest
+                    .base()
+                    .add_bits_offset(self.entry.shm_links_offset as usize)
+                    as *const ShmLink,
+                self.entry.shm_links_count as usize,
+            )
+        }
+    }
+}
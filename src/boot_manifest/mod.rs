@@ -0,0 +1,3 @@
+pub mod boot_manifest;
+pub mod format;
+pub mod manifest_items;
@@ -0,0 +1,141 @@
+//! Runtime of a child partition.
+//!
+//! A child receives its `Interface` in r0, as the root does. [`child_entry!`] declares the
+//! `start` symbol doing so and keeps the interface for [`interface`], then calls the given main
+//! function.
+//!
+//! This module also defines the panic handler of the child, which reports the panic to the
+//! parent with a [`ServiceCode::PANIC`] service call. The root partition must therefore not
+//! enable the `child` feature.
+//!
+//! [`child_entry!`]: crate::child_entry
+
+use crate::board::MemoryRange;
+use crate::core::pip_core_mpu;
+use crate::core::pip_items::Interface;
+use crate::rust::pip_rust_items::{ParentPd, PartDescId, YieldError, YieldTarget};
+use crate::rust::pip_rust_mpu;
+use crate::service::{ServiceCode, CHILD_CONTEXT_INDEX, PARENT_VIDT_INDEX, SERVICE_TAG};
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+static INTERFACE: AtomicPtr<Interface> = AtomicPtr::new(core::ptr::null_mut());
+
+/// Declares the entry point of a child partition, calling `$main` once the interface is kept.
+///
+/// ```ignore
+/// pip_mpu::child_entry!(main);
+///
+/// fn main() -> ! {
+///     let heap = pip_mpu::child::heap();
+///     loop {
+///         let _ = pip_mpu::child::yield_to_parent();
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! child_entry {
+    ($main:path) => {
+        #[no_mangle]
+        extern "C" fn start(interface: &'static $crate::core::pip_items::Interface) -> ! {
+            $crate::child::init(interface);
+            $main()
+        }
+    };
+}
+
+/// Keeps the interface given by the parent, called by [`child_entry!`].
+///
+/// [`child_entry!`]: crate::child_entry
+pub fn init(interface: &'static Interface) {
+    INTERFACE.store(
+        interface as *const Interface as *mut Interface,
+        Ordering::Relaxed,
+    );
+}
+
+/// The interface of the current partition.
+///
+/// Panics if called before [`init`].
+pub fn interface() -> &'static Interface {
+    let interface = INTERFACE.load(Ordering::Relaxed);
+    assert!(!interface.is_null(), "child::init was never called");
+    unsafe { &*interface }
+}
+
+/// The RAM left free by the parent, from `unused_ram_start` up to `ram_end`.
+///
+/// `ram_end` may lie after the blocks the parent kept for pip, so the range stops at the end of
+/// the block containing `unused_ram_start` when pip can find it.
+pub fn heap() -> MemoryRange {
+    let interface = interface();
+    let start = interface.unused_ram_start as usize;
+    let end = match pip_rust_mpu::find_block(
+        &PartDescId::current(interface),
+        interface.unused_ram_start as *const u32,
+    ) {
+        Ok(block) => block.end().min(interface.ram_end as usize),
+        Err(()) => interface.ram_end as usize,
+    };
+    MemoryRange {
+        start,
+        size: end.saturating_sub(start),
+    }
+}
+
+/// Calls a service of the parent, returning once the parent yields back.
+///
+/// The interrupt state of the child is kept across the call.
+pub fn service_call(code: ServiceCode, arg0: u32, arg1: u32) -> Result<(), YieldError> {
+    let interrupt_state = interrupts_enabled() as u32;
+    YieldError::check(pip_core_mpu::pip_yield_service(
+        ParentPd.yield_id() as *const u32,
+        PARENT_VIDT_INDEX,
+        CHILD_CONTEXT_INDEX,
+        interrupt_state,
+        interrupt_state,
+        SERVICE_TAG | code as u32,
+        arg0,
+        arg1,
+    ))
+}
+
+/// Gives the CPU back to the parent, returning once the parent yields back.
+pub fn yield_to_parent() -> Result<(), YieldError> {
+    service_call(ServiceCode::YIELD, 0, 0)
+}
+
+/// Virtually disables the interrupts of the current partition, see `pip_rust_mpu::set_int_state`.
+pub fn disable_interrupts() {
+    pip_rust_mpu::set_int_state(false);
+}
+
+/// Virtually enables the interrupts of the current partition.
+pub fn enable_interrupts() {
+    pip_rust_mpu::set_int_state(true);
+}
+
+pub fn interrupts_enabled() -> bool {
+    pip_rust_mpu::self_has_enabled_int()
+}
+
+/// Runs `f` with the interrupts disabled, restoring their previous state afterwards.
+pub fn without_interrupts<R>(f: impl FnOnce() -> R) -> R {
+    let enabled = interrupts_enabled();
+    disable_interrupts();
+    let result = f();
+    if enabled {
+        enable_interrupts();
+    }
+    result
+}
+
+#[cfg(not(test))]
+#[panic_handler]
+fn panic(panic_info: &core::panic::PanicInfo) -> ! {
+    let (line, column) = panic_info
+        .location()
+        .map_or((0, 0), |location| (location.line(), location.column()));
+    loop {
+        let _ = service_call(ServiceCode::PANIC, line, column);
+    }
+}
@@ -0,0 +1,6 @@
+//! Build configuration, generated by `build.rs` from `partitions.toml`.
+//!
+//! `build.rs` refuses the configurations the partitions could not be built with, so the values
+//! below are known to fit the MPU and the RAM available to the children.
+
+include!(concat!(env!("OUT_DIR"), "/partitions_config.rs"));
@@ -0,0 +1,174 @@
+//! Checks the structures shared with pip against its C header, `relocation_tools/relocator/pip-mpu.h`.
+//!
+//! build.rs lays out the header's types for the target, a structure of `pip_items` drifting from
+//! the header fails the build instead of corrupting memory once pip writes it.
+
+use crate::core::pip_items::{
+    BasicContext, BasicFrame, BlockAttr, BlockOrError, BoardContext, ExtendedContext,
+    ExtendedFrame, Frame, Interface, Reg, BASIC_FRAME_SIZE, EXTENDED_FRAME_SIZE, VIDT,
+};
+use core::mem::{offset_of, size_of};
+
+// The header's stackedContext_t has no counterpart in pip_items, its layout stays unused.
+#[allow(dead_code)]
+mod pip_abi {
+    include!(concat!(env!("OUT_DIR"), "/pip_abi.rs"));
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// The offset of `field` within the `FIELDS` of a C type, failing the build if it has no such field.
+const fn c_offset(fields: &[(&str, usize)], field: &str) -> usize {
+    let mut i = 0;
+    while i < fields.len() {
+        if str_eq(fields[i].0, field) {
+            return fields[i].1;
+        }
+        i += 1;
+    }
+    panic!("a field is missing from pip-mpu.h")
+}
+
+/// Whether the registers of `layout`, stored from `registers_offset` in the Rust frame, lie at the
+/// offsets of the C frame's fields of the same name.
+const fn frame_matches(layout: &[Reg], registers_offset: usize, fields: &[(&str, usize)]) -> bool {
+    let mut i = 0;
+    while i < layout.len() {
+        if c_offset(fields, layout[i].name()) != registers_offset + i * size_of::<u32>() {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+macro_rules! check_size {
+    ($rust:ty, $c:ident) => {
+        const _: () = assert!(
+            size_of::<$rust>() == pip_abi::$c::SIZE,
+            concat!(
+                "the size of ",
+                stringify!($rust),
+                " differs from ",
+                stringify!($c)
+            )
+        );
+    };
+}
+
+macro_rules! check_field {
+    ($rust:ty, $field:ident, $c:ident, $c_field:literal) => {
+        const _: () = assert!(
+            offset_of!($rust, $field) == c_offset(&pip_abi::$c::FIELDS, $c_field),
+            concat!(
+                stringify!($rust),
+                "::",
+                stringify!($field),
+                " is not at the offset of ",
+                stringify!($c),
+                "::",
+                $c_field
+            )
+        );
+    };
+}
+
+const _: () = assert!(BASIC_FRAME_SIZE == pip_abi::BASIC_FRAME_SIZE);
+const _: () = assert!(EXTENDED_FRAME_SIZE == pip_abi::EXTENDED_FRAME_SIZE);
+
+// The header describes the nRF52832 of the dwm1001, other boards have their own VIDT length: the
+// VIDT is checked against vidt_t with as many contexts as the board has interrupts.
+#[cfg(feature = "dwm1001")]
+const _: () = assert!(
+    <crate::board::CurrentBoard as crate::board::Board>::INTERRUPT_NUMBER
+        == pip_abi::VIDT_INTERRUPT_NUMBER
+);
+const VIDT_CONTEXTS_OFFSET: usize = c_offset(&pip_abi::vidt_t::FIELDS, "contexts");
+const VIDT_CONTEXT_SIZE: usize =
+    (pip_abi::vidt_t::SIZE - VIDT_CONTEXTS_OFFSET) / pip_abi::VIDT_INTERRUPT_NUMBER;
+const _: () = assert!(
+    size_of::<VIDT>()
+        == VIDT_CONTEXTS_OFFSET
+            + <crate::board::CurrentBoard as crate::board::Board>::INTERRUPT_NUMBER
+                * VIDT_CONTEXT_SIZE,
+    "the size of VIDT differs from vidt_t with the board's interrupts"
+);
+check_field!(VIDT, current_interrupt, vidt_t, "currentInterrupt");
+check_field!(VIDT, contexts, vidt_t, "contexts");
+
+check_size!(BlockAttr, blockAttr_t);
+check_field!(BlockAttr, local_id, blockAttr_t, "blockentryaddr");
+check_field!(BlockAttr, start_addr, blockAttr_t, "blockstartaddr");
+check_field!(BlockAttr, end_addr, blockAttr_t, "blockendaddr");
+check_field!(BlockAttr, read, blockAttr_t, "read");
+check_field!(BlockAttr, write, blockAttr_t, "write");
+check_field!(BlockAttr, exec, blockAttr_t, "exec");
+check_field!(BlockAttr, accessible, blockAttr_t, "accessible");
+
+check_size!(BlockOrError, blockOrError);
+check_field!(BlockOrError, error, blockOrError, "error");
+check_field!(BlockOrError, block_attr, blockOrError, "blockAttr");
+
+check_size!(BasicFrame, basicFrame_t);
+check_field!(BasicFrame, registers, basicFrame_t, "registers");
+check_size!(ExtendedFrame, extendedFrame_t);
+check_field!(ExtendedFrame, registers, extendedFrame_t, "registers");
+const _: () = assert!(frame_matches(
+    BasicFrame::LAYOUT,
+    offset_of!(BasicFrame, registers),
+    &pip_abi::basicFrame_t::FIELDS
+));
+const _: () = assert!(frame_matches(
+    ExtendedFrame::LAYOUT,
+    offset_of!(ExtendedFrame, registers),
+    &pip_abi::extendedFrame_t::FIELDS
+));
+
+check_size!(BasicContext, basicContext_t);
+check_field!(BasicContext, is_basic_frame, basicContext_t, "isBasicFrame");
+check_field!(BasicContext, pip_flags, basicContext_t, "pipflags");
+check_field!(BasicContext, frame, basicContext_t, "frame");
+
+check_size!(ExtendedContext, extendedContext_t);
+check_field!(
+    ExtendedContext,
+    is_basic_frame,
+    extendedContext_t,
+    "isBasicFrame"
+);
+check_field!(ExtendedContext, pip_flags, extendedContext_t, "pipflags");
+check_field!(ExtendedContext, frame, extendedContext_t, "frame");
+
+const _: () = assert!(
+    !BoardContext::IS_BASIC_FRAME || size_of::<BoardContext>() == pip_abi::basicContext_t::SIZE
+);
+
+check_size!(Interface, interface_t);
+check_field!(
+    Interface,
+    part_desc_block_id,
+    interface_t,
+    "partDescBlockId"
+);
+check_field!(Interface, stack_limit, interface_t, "stackLimit");
+check_field!(Interface, stack_top, interface_t, "stackTop");
+check_field!(Interface, vidt_start, interface_t, "vidtStart");
+check_field!(Interface, vidt_end, interface_t, "vidtEnd");
+check_field!(Interface, entry_point, interface_t, "root");
+check_field!(Interface, unused_rom_start, interface_t, "unusedRomStart");
+check_field!(Interface, rom_end, interface_t, "romEnd");
+check_field!(Interface, unused_ram_start, interface_t, "unusedRamStart");
+check_field!(Interface, ram_end, interface_t, "ramEnd");
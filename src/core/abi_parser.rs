@@ -0,0 +1,422 @@
+//! Lays out the types of pip's C header, `relocation_tools/relocator/pip-mpu.h`.
+//!
+//! This file uses nothing but `std`: `build.rs` includes it with `#[path]` to generate the layouts
+//! `src/core/abi.rs` checks `pip_items` against, the crate only builds it for its tests.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// The layout of a C type of the header: its size, alignment and the offset of each field, the
+/// fields of anonymous members included.
+pub struct CLayout {
+    pub size: usize,
+    pub align: usize,
+    pub fields: Vec<(String, usize)>,
+}
+
+impl CLayout {
+    pub fn offset(&self, field: &str) -> Option<usize> {
+        self.fields
+            .iter()
+            .find(|(name, _)| name == field)
+            .map(|(_, offset)| *offset)
+    }
+}
+
+/// The numeric `#define`s of the header and the layout of its `typedef struct`s and
+/// `typedef union`s, in order.
+pub struct Abi {
+    pub defines: HashMap<String, usize>,
+    pub types: Vec<(String, CLayout)>,
+}
+
+impl Abi {
+    pub fn layout(&self, name: &str) -> Option<&CLayout> {
+        self.types
+            .iter()
+            .find(|(type_name, _)| type_name == name)
+            .map(|(_, layout)| layout)
+    }
+
+    /// Writes `defines` and the layout of every type as Rust constants, one module per type.
+    pub fn to_rust(&self, defines: &[&str]) -> Result<String, String> {
+        let mut generated = String::new();
+        for define in defines {
+            let value = self
+                .defines
+                .get(*define)
+                .ok_or_else(|| format!("missing #define {define}"))?;
+            writeln!(generated, "pub const {define}: usize = {value};").unwrap();
+        }
+        for (name, layout) in &self.types {
+            writeln!(
+                generated,
+                "#[allow(non_snake_case)]\npub mod {name} {{\n    pub const SIZE: usize = {};\n    pub const FIELDS: [(&str, usize); {}] = [",
+                layout.size,
+                layout.fields.len()
+            )
+            .unwrap();
+            for (field, offset) in &layout.fields {
+                writeln!(generated, "        ({field:?}, {offset}),").unwrap();
+            }
+            writeln!(generated, "    ];\n}}").unwrap();
+        }
+        Ok(generated)
+    }
+}
+
+/// Splits the header in tokens, dropping the comments and the preprocessor lines but keeping the
+/// numeric `#define`s.
+fn tokenize(header: &str, defines: &mut HashMap<String, usize>) -> Vec<String> {
+    let mut code = String::new();
+    let mut rest = header;
+    while let Some(start) = rest.find("/*") {
+        code.push_str(&rest[..start]);
+        rest = rest[start..]
+            .find("*/")
+            .map_or("", |end| &rest[start + end + 2..]);
+    }
+    code.push_str(rest);
+
+    let mut tokens = Vec::new();
+    for line in code.lines() {
+        let line = line.split("//").next().unwrap().trim();
+        if let Some(directive) = line.strip_prefix('#') {
+            let words: Vec<&str> = directive.split_whitespace().collect();
+            if let ["define", name, value] = words[..] {
+                if let Ok(value) = value.parse() {
+                    defines.insert(name.to_string(), value);
+                }
+            }
+            continue;
+        }
+        let mut token = String::new();
+        for c in line.chars() {
+            if c.is_alphanumeric() || c == '_' {
+                token.push(c);
+                continue;
+            }
+            if !token.is_empty() {
+                tokens.push(std::mem::take(&mut token));
+            }
+            if !c.is_whitespace() {
+                tokens.push(c.to_string());
+            }
+        }
+        if !token.is_empty() {
+            tokens.push(token);
+        }
+    }
+    tokens
+}
+
+/// The token at `pos`, moving `pos` past it.
+fn next<'a>(tokens: &'a [String], pos: &mut usize) -> Result<&'a str, String> {
+    *pos += 1;
+    tokens
+        .get(*pos - 1)
+        .map(String::as_str)
+        .ok_or_else(|| "unexpected end".to_string())
+}
+
+/// Lays out the members of a `struct` or `union` body, `pos` being right after its `{`.
+fn parse_body(
+    tokens: &[String],
+    pos: &mut usize,
+    is_union: bool,
+    types: &HashMap<String, (usize, usize)>,
+    defines: &HashMap<String, usize>,
+    pointer_size: usize,
+) -> Result<CLayout, String> {
+    let mut layout = CLayout {
+        size: 0,
+        align: 1,
+        fields: Vec::new(),
+    };
+
+    loop {
+        let (size, align, fields) = match next(tokens, pos)? {
+            "}" => break,
+            keyword @ ("struct" | "union") => {
+                if next(tokens, pos)? != "{" {
+                    return Err(format!("unsupported named {keyword} member"));
+                }
+                let inner = parse_body(
+                    tokens,
+                    pos,
+                    keyword == "union",
+                    types,
+                    defines,
+                    pointer_size,
+                )?;
+                let name = next(tokens, pos)?;
+                if name != ";" {
+                    return Err(format!("unsupported named anonymous {keyword} {name}"));
+                }
+                (inner.size, inner.align, inner.fields)
+            }
+            ty => {
+                let mut name = next(tokens, pos)?;
+                let (mut size, align) = if name == "*" {
+                    name = next(tokens, pos)?;
+                    (pointer_size, pointer_size)
+                } else {
+                    *types.get(ty).ok_or_else(|| format!("unknown type {ty}"))?
+                };
+                match next(tokens, pos)? {
+                    ";" => {}
+                    "[" => {
+                        let count = next(tokens, pos)?;
+                        size *= match count.parse() {
+                            Ok(count) => count,
+                            Err(_) => *defines
+                                .get(count)
+                                .ok_or_else(|| format!("unknown length {count}"))?,
+                        };
+                        if next(tokens, pos)? != "]" || next(tokens, pos)? != ";" {
+                            return Err(format!("unsupported declaration of {name}"));
+                        }
+                    }
+                    token => return Err(format!("unexpected {token} after {name}")),
+                }
+                (size, align, vec![(name.to_string(), 0)])
+            }
+        };
+
+        let offset = if is_union {
+            0
+        } else {
+            layout.size.next_multiple_of(align)
+        };
+        layout.fields.extend(
+            fields
+                .into_iter()
+                .map(|(name, field_offset)| (name, offset + field_offset)),
+        );
+        layout.size = if is_union {
+            layout.size.max(size)
+        } else {
+            offset + size
+        };
+        layout.align = layout.align.max(align);
+    }
+    layout.size = layout.size.next_multiple_of(layout.align);
+    Ok(layout)
+}
+
+/// Lays out every `typedef struct` and `typedef union` of `header` for a target whose pointers are
+/// `pointer_size` bytes long.
+pub fn parse(header: &str, pointer_size: usize) -> Result<Abi, String> {
+    let mut defines = HashMap::new();
+    let tokens = tokenize(header, &mut defines);
+    let mut sizes: HashMap<String, (usize, usize)> = [
+        ("uint8_t", (1, 1)),
+        ("uint16_t", (2, 2)),
+        ("uint32_t", (4, 4)),
+        ("int32_t", (4, 4)),
+    ]
+    .into_iter()
+    .map(|(ty, layout)| (ty.to_string(), layout))
+    .collect();
+    let mut types = Vec::new();
+
+    let mut pos = 0;
+    while pos < tokens.len() {
+        let is_typedef_body = tokens[pos] == "typedef"
+            && matches!(
+                tokens.get(pos + 1).map(String::as_str),
+                Some("struct" | "union")
+            );
+        if !is_typedef_body {
+            pos += 1;
+            continue;
+        }
+        let is_union = tokens[pos + 1] == "union";
+        pos += 2;
+        while next(&tokens, &mut pos)? != "{" {}
+        let mut layout = parse_body(&tokens, &mut pos, is_union, &sizes, &defines, pointer_size)?;
+
+        // `__attribute__((packed))` drops the padding, the layout is only checked against the
+        // fields' offsets and the size.
+        if tokens.get(pos).map(String::as_str) == Some("__attribute__") {
+            let packed = tokens.get(pos + 3).map(String::as_str) == Some("packed");
+            pos += 6;
+            if packed {
+                layout.align = 1;
+            }
+        }
+        let name = next(&tokens, &mut pos)?.to_string();
+        sizes.insert(name.clone(), (layout.size, layout.align));
+        types.push((name, layout));
+    }
+    Ok(Abi { defines, types })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PIP_HEADER: &str = include_str!("../../relocation_tools/relocator/pip-mpu.h");
+
+    /// The header laid out for ARMv7-M, whose pointers are 4 bytes long.
+    fn armv7m() -> Abi {
+        parse(PIP_HEADER, 4).unwrap()
+    }
+
+    #[test]
+    fn defines() {
+        let abi = armv7m();
+        assert_eq!(abi.defines["VIDT_INTERRUPT_NUMBER"], 54);
+        assert_eq!(abi.defines["BASIC_FRAME_SIZE"], 17);
+        assert_eq!(abi.defines["EXTENDED_FRAME_SIZE"], 50);
+    }
+
+    #[test]
+    fn every_typedef_is_laid_out() {
+        let abi = armv7m();
+        let names: Vec<&str> = abi.types.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "blockAttr_t",
+                "blockOrError",
+                "vidt_t",
+                "basicFrame_t",
+                "extendedFrame_t",
+                "basicContext_t",
+                "extendedContext_t",
+                "stackedContext_t",
+                "interface_t",
+            ]
+        );
+    }
+
+    #[test]
+    fn block_attr() {
+        let abi = armv7m();
+        let block_attr = abi.layout("blockAttr_t").unwrap();
+        assert_eq!((block_attr.size, block_attr.align), (28, 1));
+        for (field, offset) in [
+            ("blockentryaddr", 0),
+            ("blockstartaddr", 4),
+            ("blockendaddr", 8),
+            ("read", 12),
+            ("write", 16),
+            ("exec", 20),
+            ("accessible", 24),
+        ] {
+            assert_eq!(block_attr.offset(field), Some(offset), "{field}");
+        }
+
+        let block_or_error = abi.layout("blockOrError").unwrap();
+        assert_eq!(block_or_error.size, 28);
+        assert_eq!(block_or_error.offset("error"), Some(0));
+        assert_eq!(block_or_error.offset("blockAttr"), Some(0));
+    }
+
+    #[test]
+    fn vidt() {
+        let abi = armv7m();
+        let vidt = abi.layout("vidt_t").unwrap();
+        assert_eq!(vidt.size, 4 + 54 * 4);
+        assert_eq!(vidt.offset("currentInterrupt"), Some(0));
+        assert_eq!(vidt.offset("contexts"), Some(4));
+    }
+
+    #[test]
+    fn frames_and_contexts() {
+        let abi = armv7m();
+        let basic = abi.layout("basicFrame_t").unwrap();
+        assert_eq!(basic.size, 17 * 4);
+        assert_eq!(basic.offset("sp"), Some(0));
+        assert_eq!(basic.offset("r0"), Some(9 * 4));
+        assert_eq!(basic.offset("xpsr"), Some(16 * 4));
+        assert_eq!(basic.offset("registers"), Some(0));
+
+        let extended = abi.layout("extendedFrame_t").unwrap();
+        assert_eq!(extended.size, 50 * 4);
+        assert_eq!(extended.offset("s16"), Some(0));
+        assert_eq!(extended.offset("sp"), Some(16 * 4));
+        assert_eq!(extended.offset("fpscr"), Some(49 * 4));
+
+        let basic_context = abi.layout("basicContext_t").unwrap();
+        assert_eq!(basic_context.size, 8 + 17 * 4);
+        assert_eq!(basic_context.offset("pipflags"), Some(4));
+        assert_eq!(basic_context.offset("frame"), Some(8));
+        assert_eq!(abi.layout("extendedContext_t").unwrap().size, 8 + 50 * 4);
+
+        let stacked = abi.layout("stackedContext_t").unwrap();
+        assert_eq!(stacked.size, 8 + 50 * 4);
+        assert_eq!(stacked.offset("basicFrame"), Some(8));
+        assert_eq!(stacked.offset("extendedFrame"), Some(8));
+    }
+
+    #[test]
+    fn interface() {
+        let abi = armv7m();
+        let interface = abi.layout("interface_t").unwrap();
+        assert_eq!(interface.size, 10 * 4);
+        for (index, field) in [
+            "partDescBlockId",
+            "stackLimit",
+            "stackTop",
+            "vidtStart",
+            "vidtEnd",
+            "root",
+            "unusedRomStart",
+            "romEnd",
+            "unusedRamStart",
+            "ramEnd",
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            assert_eq!(interface.offset(field), Some(index * 4), "{field}");
+        }
+    }
+
+    #[test]
+    fn pointers_follow_the_target() {
+        let abi = parse(PIP_HEADER, 8).unwrap();
+        let interface = abi.layout("interface_t").unwrap();
+        assert_eq!((interface.size, interface.offset("ramEnd")), (80, Some(72)));
+        let vidt = abi.layout("vidt_t").unwrap();
+        assert_eq!((vidt.size, vidt.offset("contexts")), (8 + 54 * 8, Some(8)));
+        // Packed, the block attributes stay unaligned.
+        assert_eq!(abi.layout("blockAttr_t").unwrap().size, 40);
+    }
+
+    #[test]
+    fn generated_rust() {
+        let rust = armv7m().to_rust(&["BASIC_FRAME_SIZE"]).unwrap();
+        assert!(rust.starts_with("pub const BASIC_FRAME_SIZE: usize = 17;\n"));
+        assert!(rust.contains("pub mod interface_t {\n    pub const SIZE: usize = 40;\n"));
+        assert!(rust.contains("        (\"ramEnd\", 36),\n"));
+        assert_eq!(
+            armv7m().to_rust(&["NO_SUCH_DEFINE"]).err().unwrap(),
+            "missing #define NO_SUCH_DEFINE"
+        );
+    }
+
+    #[test]
+    fn malformed_headers_are_refused() {
+        for (header, error) in [
+            ("typedef struct s { float f; } s_t;", "unknown type float"),
+            (
+                "typedef struct s { uint32_t a[N]; } s_t;",
+                "unknown length N",
+            ),
+            (
+                "typedef struct s { uint32_t a, b; } s_t;",
+                "unexpected , after a",
+            ),
+            (
+                "typedef struct s { struct t { uint32_t a; }; } s_t;",
+                "unsupported named struct member",
+            ),
+            ("typedef struct s { uint32_t a;", "unexpected end"),
+        ] {
+            assert_eq!(parse(header, 4).err().unwrap(), error, "{header}");
+        }
+    }
+}
@@ -1,2 +1,5 @@
+mod abi;
+#[cfg(test)]
+mod abi_parser;
 pub mod pip_core_mpu;
 pub mod pip_items;
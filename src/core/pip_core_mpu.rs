@@ -1,12 +1,14 @@
-use crate::pip_mpu::core::pip_items::BlockOrError;
-use core::arch::asm;
+// Off ARM the system calls only panic, leaving their arguments unused.
+#![cfg_attr(not(target_arch = "arm"), allow(unused_variables, unreachable_code))]
+
+use crate::core::pip_items::BlockOrError;
 
 #[inline]
 pub fn pip_create_partition(block_local_id: *const u32) -> u32 {
     let could_create_partition: u32;
     unsafe {
         //no memory clobber, it is default behavior in rust
-        asm!(
+        arm_asm!(
             "svc #0",
             inout("r0") block_local_id => could_create_partition,
         );
@@ -23,7 +25,7 @@ pub fn pip_cut_memory_block(
 ) -> *const u32 {
     let id_new_sub_block: *const u32;
     unsafe {
-        asm!(
+        arm_asm!(
             "svc #1",
             inout("r0") block_to_cut_local_id => id_new_sub_block,
             in("r1") cut_addr,
@@ -42,7 +44,7 @@ pub fn pip_merge_memory_blocks(
 ) -> *const u32 {
     let id_block_to_merge_1: *const u32;
     unsafe {
-        asm!(
+        arm_asm!(
             "svc #2",
             inout("r0") block_to_merge_1_local_id => id_block_to_merge_1,
             in("r1") block_to_merge_2_local_id,
@@ -61,7 +63,7 @@ pub fn pip_prepare(
 ) -> u32 {
     let could_prepare: u32;
     unsafe {
-        asm!(
+        arm_asm!(
             "svc #3",
             inout("r0") part_desc_block_id => could_prepare,
             in("r1") projected_slots_nb,
@@ -76,17 +78,15 @@ pub fn pip_prepare(
 pub fn pip_add_memory_block(
     child_part_desc_block_local_id: *const u32,
     block_to_share_local_id: *const u32,
-    r: u32,
-    w: u32,
-    e: u32,
+    rights: u32, //Packed as (r << 2) | (w << 1) | e
 ) -> *const u32 {
     let block_to_share_child_entry_addr: *const u32;
     unsafe {
-        asm!(
+        arm_asm!(
             "svc #4",
             inout("r0") child_part_desc_block_local_id => block_to_share_child_entry_addr,
             in("r1") block_to_share_local_id,
-            in("r2") ((r & 1) << 2) | ((w & 1) << 1) | (e & 1),
+            in("r2") rights & 0b111,
         );
     }
 
@@ -97,7 +97,7 @@ pub fn pip_add_memory_block(
 pub fn pip_remove_memory_block(block_to_remove_local_id: *const u32) -> u32 {
     let could_remove_memory_block: u32;
     unsafe {
-        asm!(
+        arm_asm!(
             "svc #5",
             inout("r0") block_to_remove_local_id => could_remove_memory_block,
         );
@@ -110,7 +110,7 @@ pub fn pip_remove_memory_block(block_to_remove_local_id: *const u32) -> u32 {
 pub fn pip_delete_partition(child_part_desc_block_local_id: *const u32) -> u32 {
     let could_delete_partition: u32;
     unsafe {
-        asm!(
+        arm_asm!(
             "svc #6",
             inout("r0") child_part_desc_block_local_id => could_delete_partition,
         );
@@ -123,7 +123,7 @@ pub fn pip_delete_partition(child_part_desc_block_local_id: *const u32) -> u32 {
 pub fn pip_collect(part_desc_block_id: *const u32) -> *const u32 {
     let collected_structure_block_id: *const u32;
     unsafe {
-        asm!(
+        arm_asm!(
             "svc #7",
             inout("r0") part_desc_block_id => collected_structure_block_id,
         );
@@ -140,7 +140,7 @@ pub fn pip_map_mpu(
 ) -> u32 {
     let could_map_mpu: u32;
     unsafe {
-        asm!(
+        arm_asm!(
             "svc #8",
             inout("r0") part_desc_block_id => could_map_mpu,
             in("r1") block_to_map_local_id,
@@ -155,7 +155,7 @@ pub fn pip_map_mpu(
 pub fn pip_read_mpu(part_desc_block_id: *const u32, mpu_region_nb: i32) -> *const u32 {
     let id_block: *const u32;
     unsafe {
-        asm!(
+        arm_asm!(
             "svc #9",
             inout("r0") part_desc_block_id => id_block,
             in("r1") mpu_region_nb,
@@ -169,11 +169,11 @@ pub fn pip_read_mpu(part_desc_block_id: *const u32, mpu_region_nb: i32) -> *cons
 pub fn pip_find_block(
     part_desc_block_id: *const u32,
     addr_in_block: *const u32,
-    block_addr: &*const BlockOrError,
+    block_addr: *mut BlockOrError,
 ) -> u32 {
     let could_find_block: u32;
     unsafe {
-        asm!(
+        arm_asm!(
             "svc #10",
             inout("r0") part_desc_block_id => could_find_block,
             in("r1") addr_in_block,
@@ -188,7 +188,7 @@ pub fn pip_find_block(
 pub fn pip_set_vidt(part_desc_block_id: *const u32, vidt_address: *const u32) -> u32 {
     let vidt_block_added: u32;
     unsafe {
-        asm!(
+        arm_asm!(
             "svc #11",
             inout("r0") part_desc_block_id => vidt_block_added,
             in("r1") vidt_address,
@@ -208,13 +208,44 @@ pub fn pip_yield(
 ) -> u32 {
     let could_yield: u32;
     unsafe {
-        asm!(
+        arm_asm!(
+            "svc #12",
+            inout("r0") callee_part_desc_block_id => could_yield,
+            in("r1") user_target_interrupt,
+            in("r2") user_caller_context_save_index,
+            in("r3") flags_on_yield,
+            in("r4") flags_on_wake,
+        );
+    }
+
+    could_yield
+}
+
+/// `pip_yield`, with `service_code`, `arg0` and `arg1` left in r5, r8 and r10 for the callee to
+/// read in the saved context of the caller.
+#[inline]
+pub fn pip_yield_service(
+    callee_part_desc_block_id: *const u32,
+    user_target_interrupt: u32,
+    user_caller_context_save_index: u32,
+    flags_on_yield: u32,
+    flags_on_wake: u32,
+    service_code: u32,
+    arg0: u32,
+    arg1: u32,
+) -> u32 {
+    let could_yield: u32;
+    unsafe {
+        arm_asm!(
             "svc #12",
             inout("r0") callee_part_desc_block_id => could_yield,
             in("r1") user_target_interrupt,
             in("r2") user_caller_context_save_index,
             in("r3") flags_on_yield,
             in("r4") flags_on_wake,
+            in("r5") service_code,
+            in("r8") arg0,
+            in("r10") arg1,
         );
     }
 
@@ -225,7 +256,7 @@ pub fn pip_yield(
 pub fn pip_get_int_state(child_part_desc_block_local_id: *const u32) -> u32 {
     let interrupt_state: u32;
     unsafe {
-        asm!(
+        arm_asm!(
             "svc #13",
             inout("r0") child_part_desc_block_local_id => interrupt_state,
         );
@@ -238,7 +269,7 @@ pub fn pip_get_int_state(child_part_desc_block_local_id: *const u32) -> u32 {
 pub fn pip_get_self_int_state() -> u32 {
     let interrupt_state: u32;
     unsafe {
-        asm!(
+        arm_asm!(
             "svc #14",
             out("r0") interrupt_state,
         );
@@ -250,18 +281,20 @@ pub fn pip_get_self_int_state() -> u32 {
 #[inline]
 pub fn pip_set_int_state(interrupt_state: u32) {
     unsafe {
-        asm!(
+        arm_asm!(
             "svc #15",
             in("r0") interrupt_state,
         );
     }
 }
 
+/// Reads the system register `register_id`. The ids pip accepts are defined by pip itself, and are
+/// not part of `pip-mpu.h`: `pip_rust_mpu` has no typed wrapper for this call and `pip_out`.
 #[inline]
 pub fn pip_in(register_id: u32) -> u32 {
     let data: u32;
     unsafe {
-        asm!(
+        arm_asm!(
             "svc #16",
             out("r1") data,
             in("r0") register_id,
@@ -271,10 +304,11 @@ pub fn pip_in(register_id: u32) -> u32 {
     data
 }
 
+/// Writes `value` to the system register `register_id`, see `pip_in`.
 #[inline]
 pub fn pip_out(register_id: u32, value: u32) {
     unsafe {
-        asm!(
+        arm_asm!(
             "svc #17",
             in("r0") register_id,
             in("r1") value,
@@ -0,0 +1,1117 @@
+//use core::slice;
+use crate::board::{Board, BoardFrame, CurrentBoard, MemoryRange};
+use core::fmt;
+use enum_from_derive::FromU32;
+
+pub(crate) const BASIC_FRAME_SIZE: usize = 17;
+pub(crate) const EXTENDED_FRAME_SIZE: usize = 50;
+const VIDT_INTERRUPT_NUMBER: usize = <CurrentBoard as Board>::INTERRUPT_NUMBER;
+
+#[repr(C)]
+pub struct VIDT {
+    pub current_interrupt: u32,
+    pub contexts: [*const u8; VIDT_INTERRUPT_NUMBER],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct BlockAttr {
+    pub local_id: *const u32,
+    pub start_addr: *const u32,
+    pub end_addr: *const u32,
+    pub read: u32,
+    pub write: u32,
+    pub exec: u32,
+    pub accessible: u32,
+}
+
+impl BlockAttr {
+    pub fn new() -> Self {
+        Self {
+            local_id: core::ptr::null(),
+            start_addr: core::ptr::null(),
+            end_addr: core::ptr::null(),
+            read: 0,
+            write: 0,
+            exec: 0,
+            accessible: 0,
+        }
+    }
+}
+
+impl Default for BlockAttr {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What `findBlock` writes: the attributes of the found block, or -1 in `error` if it is empty.
+#[repr(C)]
+pub union BlockOrError {
+    pub error: i32,
+    pub block_attr: BlockAttr,
+}
+
+impl BlockOrError {
+    pub fn new() -> Self {
+        Self {
+            block_attr: BlockAttr::new(),
+        }
+    }
+}
+
+impl Default for BlockOrError {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+/*
+    #[repr(C)]
+    pub BasicFrame {
+        sp: u32,
+        r4: u32,
+        r5: u32,
+        r6: u32,
+        r7: u32,
+        r8: u32,
+        r9: u32,
+        r10: u32,
+        r11: u32,
+        r0: u32,
+        r1: u32,
+        r2: u32,
+        r3: u32,
+        r12: u32,
+        lr: u32,
+        pc: u32,
+        xpsr: u32,
+    }
+*/
+/// A register saved in a frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reg {
+    R0,
+    R1,
+    R2,
+    R3,
+    R4,
+    R5,
+    R6,
+    R7,
+    R8,
+    R9,
+    R10,
+    R11,
+    R12,
+    SP,
+    LR,
+    PC,
+    XPSR,
+    S0,
+    S1,
+    S2,
+    S3,
+    S4,
+    S5,
+    S6,
+    S7,
+    S8,
+    S9,
+    S10,
+    S11,
+    S12,
+    S13,
+    S14,
+    S15,
+    S16,
+    S17,
+    S18,
+    S19,
+    S20,
+    S21,
+    S22,
+    S23,
+    S24,
+    S25,
+    S26,
+    S27,
+    S28,
+    S29,
+    S30,
+    S31,
+    FPSCR,
+}
+
+impl Reg {
+    /// Every register, core registers first.
+    pub const ORDER: [Reg; 50] = [
+        Reg::R0,
+        Reg::R1,
+        Reg::R2,
+        Reg::R3,
+        Reg::R4,
+        Reg::R5,
+        Reg::R6,
+        Reg::R7,
+        Reg::R8,
+        Reg::R9,
+        Reg::R10,
+        Reg::R11,
+        Reg::R12,
+        Reg::SP,
+        Reg::LR,
+        Reg::PC,
+        Reg::XPSR,
+        Reg::S0,
+        Reg::S1,
+        Reg::S2,
+        Reg::S3,
+        Reg::S4,
+        Reg::S5,
+        Reg::S6,
+        Reg::S7,
+        Reg::S8,
+        Reg::S9,
+        Reg::S10,
+        Reg::S11,
+        Reg::S12,
+        Reg::S13,
+        Reg::S14,
+        Reg::S15,
+        Reg::S16,
+        Reg::S17,
+        Reg::S18,
+        Reg::S19,
+        Reg::S20,
+        Reg::S21,
+        Reg::S22,
+        Reg::S23,
+        Reg::S24,
+        Reg::S25,
+        Reg::S26,
+        Reg::S27,
+        Reg::S28,
+        Reg::S29,
+        Reg::S30,
+        Reg::S31,
+        Reg::FPSCR,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Reg::R0 => "r0",
+            Reg::R1 => "r1",
+            Reg::R2 => "r2",
+            Reg::R3 => "r3",
+            Reg::R4 => "r4",
+            Reg::R5 => "r5",
+            Reg::R6 => "r6",
+            Reg::R7 => "r7",
+            Reg::R8 => "r8",
+            Reg::R9 => "r9",
+            Reg::R10 => "r10",
+            Reg::R11 => "r11",
+            Reg::R12 => "r12",
+            Reg::SP => "sp",
+            Reg::LR => "lr",
+            Reg::PC => "pc",
+            Reg::XPSR => "xpsr",
+            Reg::S0 => "s0",
+            Reg::S1 => "s1",
+            Reg::S2 => "s2",
+            Reg::S3 => "s3",
+            Reg::S4 => "s4",
+            Reg::S5 => "s5",
+            Reg::S6 => "s6",
+            Reg::S7 => "s7",
+            Reg::S8 => "s8",
+            Reg::S9 => "s9",
+            Reg::S10 => "s10",
+            Reg::S11 => "s11",
+            Reg::S12 => "s12",
+            Reg::S13 => "s13",
+            Reg::S14 => "s14",
+            Reg::S15 => "s15",
+            Reg::S16 => "s16",
+            Reg::S17 => "s17",
+            Reg::S18 => "s18",
+            Reg::S19 => "s19",
+            Reg::S20 => "s20",
+            Reg::S21 => "s21",
+            Reg::S22 => "s22",
+            Reg::S23 => "s23",
+            Reg::S24 => "s24",
+            Reg::S25 => "s25",
+            Reg::S26 => "s26",
+            Reg::S27 => "s27",
+            Reg::S28 => "s28",
+            Reg::S29 => "s29",
+            Reg::S30 => "s30",
+            Reg::S31 => "s31",
+            Reg::FPSCR => "fpscr",
+        }
+    }
+}
+
+impl fmt::Display for Reg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+// Frame trait allows for Generic Frame, replacing StackedFrame
+//
+// A frame only gives its registers and their layout, every accessor is built on `get` and `set`.
+pub trait Frame {
+    /// The register held at each index of `registers()`, in order.
+    const LAYOUT: &'static [Reg];
+
+    fn registers(&self) -> &[u32];
+    fn registers_mut(&mut self) -> &mut [u32];
+
+    /// The value of `reg`, `None` if the frame does not hold it.
+    fn get(&self, reg: Reg) -> Option<u32> {
+        let index = Self::LAYOUT.iter().position(|r| *r == reg)?;
+        Some(self.registers()[index])
+    }
+
+    /// Sets `reg` to `val`, failing if the frame does not hold it.
+    fn set(&mut self, reg: Reg, val: u32) -> Result<(), ()> {
+        let index = Self::LAYOUT.iter().position(|r| *r == reg).ok_or(())?;
+        self.registers_mut()[index] = val;
+        Ok(())
+    }
+
+    /// Every register of the frame with its value, in the order of `LAYOUT`.
+    fn iter(&self) -> impl Iterator<Item = (Reg, u32)> + '_ {
+        Self::LAYOUT
+            .iter()
+            .copied()
+            .zip(self.registers().iter().copied())
+    }
+
+    fn sp(&self) -> u32 {
+        self.get(Reg::SP).unwrap()
+    }
+    fn r4(&self) -> u32 {
+        self.get(Reg::R4).unwrap()
+    }
+    fn r5(&self) -> u32 {
+        self.get(Reg::R5).unwrap()
+    }
+    fn r6(&self) -> u32 {
+        self.get(Reg::R6).unwrap()
+    }
+    fn r7(&self) -> u32 {
+        self.get(Reg::R7).unwrap()
+    }
+    fn r8(&self) -> u32 {
+        self.get(Reg::R8).unwrap()
+    }
+    fn r9(&self) -> u32 {
+        self.get(Reg::R9).unwrap()
+    }
+    fn r10(&self) -> u32 {
+        self.get(Reg::R10).unwrap()
+    }
+    fn r11(&self) -> u32 {
+        self.get(Reg::R11).unwrap()
+    }
+    fn r0(&self) -> u32 {
+        self.get(Reg::R0).unwrap()
+    }
+    fn r1(&self) -> u32 {
+        self.get(Reg::R1).unwrap()
+    }
+    fn r2(&self) -> u32 {
+        self.get(Reg::R2).unwrap()
+    }
+    fn r3(&self) -> u32 {
+        self.get(Reg::R3).unwrap()
+    }
+    fn r12(&self) -> u32 {
+        self.get(Reg::R12).unwrap()
+    }
+    fn lr(&self) -> u32 {
+        self.get(Reg::LR).unwrap()
+    }
+    fn pc(&self) -> u32 {
+        self.get(Reg::PC).unwrap()
+    }
+    fn xpsr(&self) -> u32 {
+        self.get(Reg::XPSR).unwrap()
+    }
+
+    fn set_sp(&mut self, val: u32) {
+        self.set(Reg::SP, val).unwrap()
+    }
+    fn set_r4(&mut self, val: u32) {
+        self.set(Reg::R4, val).unwrap()
+    }
+    fn set_r5(&mut self, val: u32) {
+        self.set(Reg::R5, val).unwrap()
+    }
+    fn set_r6(&mut self, val: u32) {
+        self.set(Reg::R6, val).unwrap()
+    }
+    fn set_r7(&mut self, val: u32) {
+        self.set(Reg::R7, val).unwrap()
+    }
+    fn set_r8(&mut self, val: u32) {
+        self.set(Reg::R8, val).unwrap()
+    }
+    fn set_r9(&mut self, val: u32) {
+        self.set(Reg::R9, val).unwrap()
+    }
+    fn set_r10(&mut self, val: u32) {
+        self.set(Reg::R10, val).unwrap()
+    }
+    fn set_r11(&mut self, val: u32) {
+        self.set(Reg::R11, val).unwrap()
+    }
+    fn set_r0(&mut self, val: u32) {
+        self.set(Reg::R0, val).unwrap()
+    }
+    fn set_r1(&mut self, val: u32) {
+        self.set(Reg::R1, val).unwrap()
+    }
+    fn set_r2(&mut self, val: u32) {
+        self.set(Reg::R2, val).unwrap()
+    }
+    fn set_r3(&mut self, val: u32) {
+        self.set(Reg::R3, val).unwrap()
+    }
+    fn set_r12(&mut self, val: u32) {
+        self.set(Reg::R12, val).unwrap()
+    }
+    fn set_lr(&mut self, val: u32) {
+        self.set(Reg::LR, val).unwrap()
+    }
+    fn set_pc(&mut self, val: u32) {
+        self.set(Reg::PC, val).unwrap()
+    }
+    fn set_xpsr(&mut self, val: u32) {
+        self.set(Reg::XPSR, val).unwrap()
+    }
+}
+
+/// Writes the registers of `frame` four per line, core registers first.
+fn fmt_frame(frame: &impl Frame, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let mut regs: [Option<(Reg, u32)>; EXTENDED_FRAME_SIZE] = [None; EXTENDED_FRAME_SIZE];
+    for (slot, reg) in regs.iter_mut().zip(
+        Reg::ORDER
+            .iter()
+            .filter_map(|reg| frame.get(*reg).map(|val| (*reg, val))),
+    ) {
+        *slot = Some(reg);
+    }
+    for (i, (reg, val)) in regs.iter().flatten().enumerate() {
+        if i > 0 {
+            f.write_str(if i % 4 == 0 { "\n" } else { " " })?;
+        }
+        write!(f, "{:>5} {val:#010x}", reg.name())?;
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+#[repr(C)]
+pub struct BasicFrame {
+    pub registers: [u32; BASIC_FRAME_SIZE],
+}
+
+impl Frame for BasicFrame {
+    const LAYOUT: &'static [Reg] = &[
+        Reg::SP,
+        Reg::R4,
+        Reg::R5,
+        Reg::R6,
+        Reg::R7,
+        Reg::R8,
+        Reg::R9,
+        Reg::R10,
+        Reg::R11,
+        Reg::R0,
+        Reg::R1,
+        Reg::R2,
+        Reg::R3,
+        Reg::R12,
+        Reg::LR,
+        Reg::PC,
+        Reg::XPSR,
+    ];
+
+    fn registers(&self) -> &[u32] {
+        &self.registers
+    }
+    fn registers_mut(&mut self) -> &mut [u32] {
+        &mut self.registers
+    }
+}
+
+/// The frame pip saves for a partition which used the FPU: the basic frame, s0-s31 and fpscr.
+#[repr(C)]
+pub struct ExtendedFrame {
+    pub registers: [u32; EXTENDED_FRAME_SIZE],
+}
+
+impl Default for ExtendedFrame {
+    fn default() -> Self {
+        Self {
+            registers: [0; EXTENDED_FRAME_SIZE],
+        }
+    }
+}
+
+impl Frame for ExtendedFrame {
+    const LAYOUT: &'static [Reg] = &[
+        Reg::S16,
+        Reg::S17,
+        Reg::S18,
+        Reg::S19,
+        Reg::S20,
+        Reg::S21,
+        Reg::S22,
+        Reg::S23,
+        Reg::S24,
+        Reg::S25,
+        Reg::S26,
+        Reg::S27,
+        Reg::S28,
+        Reg::S29,
+        Reg::S30,
+        Reg::S31,
+        Reg::SP,
+        Reg::R4,
+        Reg::R5,
+        Reg::R6,
+        Reg::R7,
+        Reg::R8,
+        Reg::R9,
+        Reg::R10,
+        Reg::R11,
+        Reg::R0,
+        Reg::R1,
+        Reg::R2,
+        Reg::R3,
+        Reg::R12,
+        Reg::LR,
+        Reg::PC,
+        Reg::XPSR,
+        Reg::S0,
+        Reg::S1,
+        Reg::S2,
+        Reg::S3,
+        Reg::S4,
+        Reg::S5,
+        Reg::S6,
+        Reg::S7,
+        Reg::S8,
+        Reg::S9,
+        Reg::S10,
+        Reg::S11,
+        Reg::S12,
+        Reg::S13,
+        Reg::S14,
+        Reg::S15,
+        Reg::FPSCR,
+    ];
+
+    fn registers(&self) -> &[u32] {
+        &self.registers
+    }
+    fn registers_mut(&mut self) -> &mut [u32] {
+        &mut self.registers
+    }
+}
+
+impl ExtendedFrame {
+    pub fn s16(&self) -> u32 {
+        self.get(Reg::S16).unwrap()
+    }
+    pub fn s17(&self) -> u32 {
+        self.get(Reg::S17).unwrap()
+    }
+    pub fn s18(&self) -> u32 {
+        self.get(Reg::S18).unwrap()
+    }
+    pub fn s19(&self) -> u32 {
+        self.get(Reg::S19).unwrap()
+    }
+    pub fn s20(&self) -> u32 {
+        self.get(Reg::S20).unwrap()
+    }
+    pub fn s21(&self) -> u32 {
+        self.get(Reg::S21).unwrap()
+    }
+    pub fn s22(&self) -> u32 {
+        self.get(Reg::S22).unwrap()
+    }
+    pub fn s23(&self) -> u32 {
+        self.get(Reg::S23).unwrap()
+    }
+    pub fn s24(&self) -> u32 {
+        self.get(Reg::S24).unwrap()
+    }
+    pub fn s25(&self) -> u32 {
+        self.get(Reg::S25).unwrap()
+    }
+    pub fn s26(&self) -> u32 {
+        self.get(Reg::S26).unwrap()
+    }
+    pub fn s27(&self) -> u32 {
+        self.get(Reg::S27).unwrap()
+    }
+    pub fn s28(&self) -> u32 {
+        self.get(Reg::S28).unwrap()
+    }
+    pub fn s29(&self) -> u32 {
+        self.get(Reg::S29).unwrap()
+    }
+    pub fn s30(&self) -> u32 {
+        self.get(Reg::S30).unwrap()
+    }
+    pub fn s31(&self) -> u32 {
+        self.get(Reg::S31).unwrap()
+    }
+    pub fn s0(&self) -> u32 {
+        self.get(Reg::S0).unwrap()
+    }
+    pub fn s1(&self) -> u32 {
+        self.get(Reg::S1).unwrap()
+    }
+    pub fn s2(&self) -> u32 {
+        self.get(Reg::S2).unwrap()
+    }
+    pub fn s3(&self) -> u32 {
+        self.get(Reg::S3).unwrap()
+    }
+    pub fn s4(&self) -> u32 {
+        self.get(Reg::S4).unwrap()
+    }
+    pub fn s5(&self) -> u32 {
+        self.get(Reg::S5).unwrap()
+    }
+    pub fn s6(&self) -> u32 {
+        self.get(Reg::S6).unwrap()
+    }
+    pub fn s7(&self) -> u32 {
+        self.get(Reg::S7).unwrap()
+    }
+    pub fn s8(&self) -> u32 {
+        self.get(Reg::S8).unwrap()
+    }
+    pub fn s9(&self) -> u32 {
+        self.get(Reg::S9).unwrap()
+    }
+    pub fn s10(&self) -> u32 {
+        self.get(Reg::S10).unwrap()
+    }
+    pub fn s11(&self) -> u32 {
+        self.get(Reg::S11).unwrap()
+    }
+    pub fn s12(&self) -> u32 {
+        self.get(Reg::S12).unwrap()
+    }
+    pub fn s13(&self) -> u32 {
+        self.get(Reg::S13).unwrap()
+    }
+    pub fn s14(&self) -> u32 {
+        self.get(Reg::S14).unwrap()
+    }
+    pub fn s15(&self) -> u32 {
+        self.get(Reg::S15).unwrap()
+    }
+    pub fn fpscr(&self) -> u32 {
+        self.get(Reg::FPSCR).unwrap()
+    }
+
+    pub fn set_s16(&mut self, val: u32) {
+        self.set(Reg::S16, val).unwrap()
+    }
+    pub fn set_s17(&mut self, val: u32) {
+        self.set(Reg::S17, val).unwrap()
+    }
+    pub fn set_s18(&mut self, val: u32) {
+        self.set(Reg::S18, val).unwrap()
+    }
+    pub fn set_s19(&mut self, val: u32) {
+        self.set(Reg::S19, val).unwrap()
+    }
+    pub fn set_s20(&mut self, val: u32) {
+        self.set(Reg::S20, val).unwrap()
+    }
+    pub fn set_s21(&mut self, val: u32) {
+        self.set(Reg::S21, val).unwrap()
+    }
+    pub fn set_s22(&mut self, val: u32) {
+        self.set(Reg::S22, val).unwrap()
+    }
+    pub fn set_s23(&mut self, val: u32) {
+        self.set(Reg::S23, val).unwrap()
+    }
+    pub fn set_s24(&mut self, val: u32) {
+        self.set(Reg::S24, val).unwrap()
+    }
+    pub fn set_s25(&mut self, val: u32) {
+        self.set(Reg::S25, val).unwrap()
+    }
+    pub fn set_s26(&mut self, val: u32) {
+        self.set(Reg::S26, val).unwrap()
+    }
+    pub fn set_s27(&mut self, val: u32) {
+        self.set(Reg::S27, val).unwrap()
+    }
+    pub fn set_s28(&mut self, val: u32) {
+        self.set(Reg::S28, val).unwrap()
+    }
+    pub fn set_s29(&mut self, val: u32) {
+        self.set(Reg::S29, val).unwrap()
+    }
+    pub fn set_s30(&mut self, val: u32) {
+        self.set(Reg::S30, val).unwrap()
+    }
+    pub fn set_s31(&mut self, val: u32) {
+        self.set(Reg::S31, val).unwrap()
+    }
+    pub fn set_s0(&mut self, val: u32) {
+        self.set(Reg::S0, val).unwrap()
+    }
+    pub fn set_s1(&mut self, val: u32) {
+        self.set(Reg::S1, val).unwrap()
+    }
+    pub fn set_s2(&mut self, val: u32) {
+        self.set(Reg::S2, val).unwrap()
+    }
+    pub fn set_s3(&mut self, val: u32) {
+        self.set(Reg::S3, val).unwrap()
+    }
+    pub fn set_s4(&mut self, val: u32) {
+        self.set(Reg::S4, val).unwrap()
+    }
+    pub fn set_s5(&mut self, val: u32) {
+        self.set(Reg::S5, val).unwrap()
+    }
+    pub fn set_s6(&mut self, val: u32) {
+        self.set(Reg::S6, val).unwrap()
+    }
+    pub fn set_s7(&mut self, val: u32) {
+        self.set(Reg::S7, val).unwrap()
+    }
+    pub fn set_s8(&mut self, val: u32) {
+        self.set(Reg::S8, val).unwrap()
+    }
+    pub fn set_s9(&mut self, val: u32) {
+        self.set(Reg::S9, val).unwrap()
+    }
+    pub fn set_s10(&mut self, val: u32) {
+        self.set(Reg::S10, val).unwrap()
+    }
+    pub fn set_s11(&mut self, val: u32) {
+        self.set(Reg::S11, val).unwrap()
+    }
+    pub fn set_s12(&mut self, val: u32) {
+        self.set(Reg::S12, val).unwrap()
+    }
+    pub fn set_s13(&mut self, val: u32) {
+        self.set(Reg::S13, val).unwrap()
+    }
+    pub fn set_s14(&mut self, val: u32) {
+        self.set(Reg::S14, val).unwrap()
+    }
+    pub fn set_s15(&mut self, val: u32) {
+        self.set(Reg::S15, val).unwrap()
+    }
+    pub fn set_fpscr(&mut self, val: u32) {
+        self.set(Reg::FPSCR, val).unwrap()
+    }
+}
+
+impl fmt::Debug for BasicFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl fmt::Debug for ExtendedFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl fmt::Display for BasicFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_frame(self, f)
+    }
+}
+
+impl fmt::Display for ExtendedFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_frame(self, f)
+    }
+}
+
+#[repr(C)]
+#[derive(Default)]
+pub struct BasicContext {
+    pub is_basic_frame: u32,
+    pub pip_flags: u32,
+    pub frame: BasicFrame,
+}
+
+/// The context of a partition on the selected board, holding a `BoardFrame`.
+#[repr(C)]
+#[derive(Default)]
+pub struct BoardContext {
+    pub is_basic_frame: u32,
+    pub pip_flags: u32,
+    pub frame: BoardFrame,
+}
+
+impl BoardContext {
+    /// Whether pip should restore the frame of this context as a basic frame.
+    pub const IS_BASIC_FRAME: bool =
+        core::mem::size_of::<BoardFrame>() == core::mem::size_of::<BasicFrame>();
+}
+
+#[repr(C)]
+#[derive(Default)]
+pub struct ExtendedContext {
+    pub is_basic_frame: u32,
+    pub pip_flags: u32,
+    pub frame: ExtendedFrame,
+}
+
+/// The frame a child is created with, which sets the size of its context.
+///
+/// A child created with a basic frame must not use the FPU: pip would save an extended frame
+/// past the end of its context.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContextKind {
+    BASIC,    //A `BoardContext`.
+    EXTENDED, //An `ExtendedContext`, for children using the FPU.
+}
+
+impl ContextKind {
+    /// The size of the context.
+    pub const fn size(self) -> usize {
+        match self {
+            ContextKind::BASIC => core::mem::size_of::<BoardContext>(),
+            ContextKind::EXTENDED => core::mem::size_of::<ExtendedContext>(),
+        }
+    }
+}
+
+pub struct StackedContect {
+    pub is_basic_frame: u32,
+}
+#[repr(C)]
+pub struct Interface {
+    /// The ID of the block containing the partition descriptor of the root partition
+    pub part_desc_block_id: *const u8,
+
+    /// The limit address of the stack of the root partition
+    pub stack_limit: *const u8,
+
+    /// The stack top address of the root partition
+    pub stack_top: *const u8,
+
+    /// The VIDT start address of the root partition
+    pub vidt_start: *const u8,
+
+    /// The VIDT end address of the root partition
+    pub vidt_end: *const u8,
+
+    /// The start address of the root partition binary
+    pub entry_point: *const u8,
+
+    /// The start address of the unused ROM
+    pub unused_rom_start: *const u8,
+
+    /// The end address of the unused ROM
+    pub rom_end: *const u8,
+
+    /// The start address of the unused RAM
+    pub unused_ram_start: *mut u8,
+
+    /// The end address of the unused RAM
+    pub ram_end: *const u8,
+}
+
+/// An invariant of `Interface` pip should have ensured, checked by `Interface::validate`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InterfaceError {
+    NULL_PART_DESC,         //The partition descriptor is null.
+    STACK_EMPTY,            //The stack limit is not below the stack top.
+    STACK_OUT_OF_RAM,       //The stack is not within the board's RAM.
+    VIDT_EMPTY,             //The VIDT start is not below the VIDT end.
+    VIDT_OUT_OF_RAM,        //The VIDT is not within the board's RAM.
+    UNUSED_RAM_PAST_END,    //The unused RAM starts after the end of the RAM.
+    RAM_OUT_OF_BOARD,       //The unused RAM is not within the board's RAM.
+    UNUSED_ROM_PAST_END,    //The unused ROM starts after the end of the ROM.
+    ROM_OUT_OF_BOARD,       //The unused ROM is not within the board's ROM.
+    ENTRY_POINT_OUT_OF_ROM, //The entry point is not within the board's ROM, before the unused ROM.
+    MISALIGNED,             //The stack, the VIDT or the entry point is misaligned.
+}
+
+impl fmt::Display for InterfaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            InterfaceError::NULL_PART_DESC => "the partition descriptor is null",
+            InterfaceError::STACK_EMPTY => "the stack limit is not below the stack top",
+            InterfaceError::STACK_OUT_OF_RAM => "the stack is not within the RAM",
+            InterfaceError::VIDT_EMPTY => "the VIDT start is not below the VIDT end",
+            InterfaceError::VIDT_OUT_OF_RAM => "the VIDT is not within the RAM",
+            InterfaceError::UNUSED_RAM_PAST_END => "the unused RAM starts after the end of the RAM",
+            InterfaceError::RAM_OUT_OF_BOARD => "the unused RAM is not within the RAM",
+            InterfaceError::UNUSED_ROM_PAST_END => "the unused ROM starts after the end of the ROM",
+            InterfaceError::ROM_OUT_OF_BOARD => "the unused ROM is not within the ROM",
+            InterfaceError::ENTRY_POINT_OUT_OF_ROM => "the entry point is not within the used ROM",
+            InterfaceError::MISALIGNED => "the stack, the VIDT or the entry point is misaligned",
+        })
+    }
+}
+
+/// Whether `[start, end)` lies within `range`, `end` being allowed to be the end of `range`.
+fn within(range: MemoryRange, start: usize, end: usize) -> bool {
+    range.contains(start) && end <= range.end()
+}
+
+impl Interface {
+    /// Checks the invariants pip ensures when giving the interface to a partition: non empty
+    /// stack and VIDT within the RAM, unused RAM and ROM within the board's, entry point in the
+    /// used ROM, and the stack and VIDT aligned on 4 bytes, the entry point on 2.
+    pub fn validate(&self) -> Result<(), InterfaceError> {
+        let ram = <CurrentBoard as Board>::RAM;
+        let rom = <CurrentBoard as Board>::ROM;
+        let (stack_limit, stack_top) = (self.stack_limit as usize, self.stack_top as usize);
+        let (vidt_start, vidt_end) = (self.vidt_start as usize, self.vidt_end as usize);
+        let (unused_ram_start, ram_end) = (self.unused_ram_start as usize, self.ram_end as usize);
+        let (unused_rom_start, rom_end) = (self.unused_rom_start as usize, self.rom_end as usize);
+        let entry_point = self.entry_point as usize;
+
+        if self.part_desc_block_id.is_null() {
+            return Err(InterfaceError::NULL_PART_DESC);
+        }
+        if stack_limit >= stack_top {
+            return Err(InterfaceError::STACK_EMPTY);
+        }
+        if !within(ram, stack_limit, stack_top) {
+            return Err(InterfaceError::STACK_OUT_OF_RAM);
+        }
+        if vidt_start >= vidt_end {
+            return Err(InterfaceError::VIDT_EMPTY);
+        }
+        if !within(ram, vidt_start, vidt_end) {
+            return Err(InterfaceError::VIDT_OUT_OF_RAM);
+        }
+        if unused_ram_start > ram_end {
+            return Err(InterfaceError::UNUSED_RAM_PAST_END);
+        }
+        if !(ram.start..=ram.end()).contains(&unused_ram_start) || ram_end > ram.end() {
+            return Err(InterfaceError::RAM_OUT_OF_BOARD);
+        }
+        if unused_rom_start > rom_end {
+            return Err(InterfaceError::UNUSED_ROM_PAST_END);
+        }
+        if !(rom.start..=rom.end()).contains(&unused_rom_start) || rom_end > rom.end() {
+            return Err(InterfaceError::ROM_OUT_OF_BOARD);
+        }
+        if !rom.contains(entry_point) || entry_point >= unused_rom_start {
+            return Err(InterfaceError::ENTRY_POINT_OUT_OF_ROM);
+        }
+        if [stack_limit, stack_top, vidt_start]
+            .iter()
+            .any(|addr| !addr.is_multiple_of(4))
+            || !entry_point.is_multiple_of(2)
+        {
+            return Err(InterfaceError::MISALIGNED);
+        }
+        Ok(())
+    }
+}
+
+/// The memory map of the partition, one area per line.
+impl fmt::Display for Interface {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "partition descriptor {:p}", self.part_desc_block_id)?;
+        for (name, start, end) in [
+            ("used ROM", self.entry_point, self.unused_rom_start),
+            ("unused ROM", self.unused_rom_start, self.rom_end),
+            ("stack", self.stack_limit, self.stack_top),
+            ("VIDT", self.vidt_start, self.vidt_end),
+            (
+                "unused RAM",
+                self.unused_ram_start as *const u8,
+                self.ram_end,
+            ),
+        ] {
+            writeln!(
+                f,
+                "{name:<10} {:#010x}-{:#010x} {:>7}",
+                start as usize,
+                end as usize,
+                (end as usize).saturating_sub(start as usize)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(FromU32)]
+pub enum YieldCode {
+    /*
+     * \brief The system call succeeds without error.
+     *
+     * \warning This value is never returned by the yield system
+     *          call, but is required for a future implementation
+     *          of the service in Coq.
+     */
+    YIELD_SUCCESS = 0,
+
+    /*
+     * \brief The VIDT index of the callee is greater than 32.
+     */
+    CALLEE_INVALID_VIDT_INDEX = 1,
+
+    /*
+     * \brief The VIDT index of the caller is greater than 32.
+     */
+    CALLER_INVALID_VIDT_INDEX = 2,
+
+    /*
+     * \brief The callee is not a child of the caller.
+     */
+    CALLEE_NOT_CHILD_OF_CALLER = 3,
+
+    /*
+     * \brief The root partition tried to call its parent.
+     */
+    CALLEE_IS_PARENT_OF_ROOT = 4,
+
+    /*
+     * \brief The address of the block containing the VIDT of the
+     *        caller is null.
+     */
+    CALLER_VIDT_IS_NULL = 5,
+
+    /*
+     * \brief The block containing the VIDT of the caller does not
+     *        have the present flag.
+     */
+    CALLER_VIDT_IS_NOT_PRESENT = 6,
+
+    /*
+     * \brief The block containing the VIDT of the caller does not
+     *        have the accessible flag.
+     */
+    CALLER_VIDT_IS_NOT_ACCESSIBLE = 7,
+
+    /*
+     * \brief The block containing the VIDT of the caller is too
+     *        small.
+     */
+    CALLER_VIDT_BLOCK_TOO_SMALL = 8,
+
+    /*
+     * \brief The address of the block containing the VIDT of the
+     *        callee is null.
+     */
+    CALLEE_VIDT_IS_NULL = 9,
+
+    /*
+     * \brief The block containing the VIDT of the callee does not
+     *        have the present flag.
+     */
+    CALLEE_VIDT_IS_NOT_PRESENT = 10,
+
+    /*
+     * \brief The block containing the VIDT of the callee does not
+     *        have the accessible flag.
+     */
+    CALLEE_VIDT_IS_NOT_ACCESSIBLE = 11,
+
+    /*
+     * \brief The block containing the VIDT of the callee is too
+     *        small.
+     */
+    CALLEE_VIDT_BLOCK_TOO_SMALL = 12,
+
+    /*
+     * \brief No block were found in the caller's address space
+     *        that match the context address read from the VIDT.
+     */
+    CALLER_CONTEXT_BLOCK_NOT_FOUND = 13,
+
+    /*
+     * \brief The block containing the address to which the context
+     *        of the caller is to be written does not have the
+     *        present flag.
+     */
+    CALLER_CONTEXT_BLOCK_IS_NOT_PRESENT = 14,
+
+    /*
+     * \brief The block containing the address to which the context
+     *        of the caller is to be written does not have the
+     *        accessible flag.
+     */
+    CALLER_CONTEXT_BLOCK_IS_NOT_ACCESSIBLE = 15,
+
+    /*
+     * \brief The block containing the address to which the context
+     *        of the caller is to be written does not have the
+     *        writable flag.
+     */
+    CALLER_CONTEXT_BLOCK_IS_NOT_WRITABLE = 16,
+
+    /*
+     * \brief The address of the caller's context, added to the
+     *        size of a context, exceeds the end of the block.
+     */
+    CALLER_CONTEXT_EXCEED_BLOCK_END = 17,
+
+    /*
+     * \brief The address to which the caller's context should be
+     *        written is not aligned on a 4-byte boundary.
+     */
+    CALLER_CONTEXT_MISALIGNED = 18,
+
+    /*
+     * \brief No block were found in the callee's address space
+     *        that match the context address read from the VIDT.
+     */
+    CALLEE_CONTEXT_BLOCK_NOT_FOUND = 19,
+
+    /*
+     * \brief The block containing the address at which the context
+     *        of the callee is to be read does not have the present
+     *        flag.
+     */
+    CALLEE_CONTEXT_BLOCK_IS_NOT_PRESENT = 20,
+
+    /*
+     * \brief The block containing the address at which the context
+     *        of the callee is to be read does not have the
+     *        accessible flag.
+     */
+    CALLEE_CONTEXT_BLOCK_IS_NOT_ACCESSIBLE = 21,
+
+    /*
+     * \brief The block containing the address at which the context
+     *        of the callee is to be read does not have the readable
+     *        flag.
+     */
+    CALLEE_CONTEXT_BLOCK_IS_NOT_READABLE = 22,
+
+    /*
+     * \brief The address of the callee's context, added to the size
+     *        of a context, exceeds the end of the block.
+     */
+    CALLEE_CONTEXT_EXCEED_BLOCK_END = 23,
+
+    /*
+     * \brief The address at which the callee's context should be
+     *        read is not aligned on a 4-byte boundary.
+     */
+    CALLEE_CONTEXT_MISALIGNED = 24,
+}
@@ -0,0 +1,225 @@
+//! Leveled logging over [`Formatter`], with the [`error!`], [`warn!`], [`info!`], [`debug!`] and
+//! [`trace!`] macros.
+//!
+//! Each record is written as one line to the current [`Sink`], prefixed with its level, the name
+//! of the emitting partition if set with [`set_partition`], and a tag, the emitting module unless
+//! given:
+//!
+//! ```ignore
+//! pip_mpu::io::log::set_partition("root");
+//! pip_mpu::info!("{} children booted", children.count);
+//! pip_mpu::warn!(tag: "manifest", "no manifest found");
+//! // INFO  root root: 2 children booted
+//! // WARN  root manifest: no manifest found
+//! ```
+//!
+//! Records above [`STATIC_MAX_LEVEL`], chosen with the `max-level-*` features, are compiled out.
+//! Records above the runtime level, see [`set_max_level`], are dropped.
+//!
+//! [`error!`]: crate::error
+//! [`warn!`]: crate::warn
+//! [`info!`]: crate::info
+//! [`debug!`]: crate::debug
+//! [`trace!`]: crate::trace
+
+use crate::io::{Formatter, SemihostingSink, Sink};
+use crate::tools::SpinLock;
+use core::fmt::{self, Write};
+use core::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Level {
+    OFF = 0, //No record at all, only meaningful as a maximum level.
+    ERROR = 1,
+    WARN = 2,
+    INFO = 3,
+    DEBUG = 4,
+    TRACE = 5,
+}
+
+impl Level {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Level::OFF => "OFF",
+            Level::ERROR => "ERROR",
+            Level::WARN => "WARN",
+            Level::INFO => "INFO",
+            Level::DEBUG => "DEBUG",
+            Level::TRACE => "TRACE",
+        }
+    }
+
+    const fn from_u8(level: u8) -> Self {
+        match level {
+            0 => Level::OFF,
+            1 => Level::ERROR,
+            2 => Level::WARN,
+            3 => Level::INFO,
+            4 => Level::DEBUG,
+            _ => Level::TRACE,
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+/// The most verbose level compiled in, set by the most restrictive `max-level-*` feature enabled.
+/// Every level is compiled in without any of them.
+#[cfg(feature = "max-level-off")]
+pub const STATIC_MAX_LEVEL: Level = Level::OFF;
+#[cfg(all(not(feature = "max-level-off"), feature = "max-level-error"))]
+pub const STATIC_MAX_LEVEL: Level = Level::ERROR;
+#[cfg(all(
+    not(any(feature = "max-level-off", feature = "max-level-error")),
+    feature = "max-level-warn"
+))]
+pub const STATIC_MAX_LEVEL: Level = Level::WARN;
+#[cfg(all(
+    not(any(
+        feature = "max-level-off",
+        feature = "max-level-error",
+        feature = "max-level-warn"
+    )),
+    feature = "max-level-info"
+))]
+pub const STATIC_MAX_LEVEL: Level = Level::INFO;
+#[cfg(all(
+    not(any(
+        feature = "max-level-off",
+        feature = "max-level-error",
+        feature = "max-level-warn",
+        feature = "max-level-info"
+    )),
+    feature = "max-level-debug"
+))]
+pub const STATIC_MAX_LEVEL: Level = Level::DEBUG;
+#[cfg(not(any(
+    feature = "max-level-off",
+    feature = "max-level-error",
+    feature = "max-level-warn",
+    feature = "max-level-info",
+    feature = "max-level-debug"
+)))]
+pub const STATIC_MAX_LEVEL: Level = Level::TRACE;
+
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(STATIC_MAX_LEVEL as u8);
+
+/// Drops the records above `level` from now on. `level` can not exceed [`STATIC_MAX_LEVEL`].
+pub fn set_max_level(level: Level) {
+    MAX_LEVEL.store(level.min(STATIC_MAX_LEVEL) as u8, Ordering::Relaxed);
+}
+
+pub fn max_level() -> Level {
+    Level::from_u8(MAX_LEVEL.load(Ordering::Relaxed))
+}
+
+/// Whether a record of `level` would be written.
+#[inline]
+pub fn enabled(level: Level) -> bool {
+    level != Level::OFF
+        && level <= STATIC_MAX_LEVEL
+        && level as u8 <= MAX_LEVEL.load(Ordering::Relaxed)
+}
+
+/// Where records are written, and the partition they are tagged with.
+#[derive(Clone, Copy)]
+struct Route {
+    sink: &'static dyn Sink,
+    partition: Option<&'static str>,
+}
+
+static LOGGER: SpinLock<Route> = SpinLock::new(Route {
+    sink: &SemihostingSink,
+    partition: None,
+});
+
+/// Writes the records to `sink` from now on, instead of the debugger's stdout.
+pub fn set_sink(sink: &'static dyn Sink) {
+    LOGGER.lock(|route| route.sink = sink);
+}
+
+/// Prefixes the records with `name`, the name of the current partition.
+pub fn set_partition(name: &'static str) {
+    LOGGER.lock(|route| route.partition = Some(name));
+}
+
+/// Writes a record, called by the logging macros once [`enabled`] holds.
+#[doc(hidden)]
+pub fn write_record(level: Level, tag: &str, args: fmt::Arguments) {
+    // The lock is not held while writing, so that a sink may log itself.
+    let route = LOGGER.lock(|route| *route);
+    let mut f = Formatter::to(route.sink);
+    let _ = match route.partition {
+        Some(partition) => writeln!(f, "{level:<5} {partition} {tag}: {args}"),
+        None => writeln!(f, "{level:<5} {tag}: {args}"),
+    };
+}
+
+/// Logs a record of the given [`Level`], tagged with `tag` or the current module.
+#[macro_export]
+macro_rules! log {
+    (tag: $tag:expr, $level:expr, $($arg:tt)+) => {{
+        let level = $level;
+        if $crate::io::log::enabled(level) {
+            $crate::io::log::write_record(level, $tag, format_args!($($arg)+));
+        }
+    }};
+    ($level:expr, $($arg:tt)+) => {
+        $crate::log!(tag: module_path!(), $level, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! error {
+    (tag: $tag:expr, $($arg:tt)+) => {
+        $crate::log!(tag: $tag, $crate::io::log::Level::ERROR, $($arg)+)
+    };
+    ($($arg:tt)+) => {
+        $crate::log!($crate::io::log::Level::ERROR, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! warn {
+    (tag: $tag:expr, $($arg:tt)+) => {
+        $crate::log!(tag: $tag, $crate::io::log::Level::WARN, $($arg)+)
+    };
+    ($($arg:tt)+) => {
+        $crate::log!($crate::io::log::Level::WARN, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! info {
+    (tag: $tag:expr, $($arg:tt)+) => {
+        $crate::log!(tag: $tag, $crate::io::log::Level::INFO, $($arg)+)
+    };
+    ($($arg:tt)+) => {
+        $crate::log!($crate::io::log::Level::INFO, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! debug {
+    (tag: $tag:expr, $($arg:tt)+) => {
+        $crate::log!(tag: $tag, $crate::io::log::Level::DEBUG, $($arg)+)
+    };
+    ($($arg:tt)+) => {
+        $crate::log!($crate::io::log::Level::DEBUG, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! trace {
+    (tag: $tag:expr, $($arg:tt)+) => {
+        $crate::log!(tag: $tag, $crate::io::log::Level::TRACE, $($arg)+)
+    };
+    ($($arg:tt)+) => {
+        $crate::log!($crate::io::log::Level::TRACE, $($arg)+)
+    };
+}
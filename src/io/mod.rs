@@ -0,0 +1,156 @@
+//! Simple IO module that uses arm semihostting
+//! to ouput bytes and strings
+
+// Off ARM the semihosting calls only panic, leaving their arguments and constants unused.
+#![cfg_attr(
+    not(target_arch = "arm"),
+    allow(dead_code, unused_variables, unreachable_code)
+)]
+
+pub mod log;
+pub mod ring;
+pub mod rtt;
+pub mod semihosting;
+
+const SYS_WRITE0: u8 = 0x4;
+const SYS_WRITEC: u8 = 0x3;
+const ANGEL_SWI: u8 = 0xab;
+
+/// The bytes a [`Formatter`] holds before flushing them.
+pub const FORMATTER_BUFFER_SIZE: usize = 128;
+
+use core::ffi::CStr;
+
+/// Where output ends up: the debugger's stdout, a ring buffer in RAM...
+pub trait Sink: Sync {
+    /// Writes out `bytes`, dropping what the sink can not take.
+    fn write(&self, bytes: &[u8]);
+}
+
+/// The debugger's stdout, through the `SYS_WRITE` semihosting call.
+pub struct SemihostingSink;
+
+impl Sink for SemihostingSink {
+    fn write(&self, bytes: &[u8]) {
+        write_all(bytes);
+    }
+}
+
+/// An object that implements [`core::fmt::Write`]
+/// to perform formatted output
+///
+/// The output is buffered, and written to the formatter's [`Sink`]
+/// at each newline, when the buffer is full, on [`Formatter::flush`]
+/// and when the formatter is dropped. [`Formatter::new`] writes to
+/// the debugger's stdout with a single `SYS_WRITE` call each time.
+///
+/// [`core::fmt::Write`]: https://doc.rust-lang.org/core/fmt/trait.Write.html
+pub struct Formatter {
+    buffer: [u8; FORMATTER_BUFFER_SIZE],
+    len: usize,
+    sink: &'static dyn Sink,
+}
+
+impl Formatter {
+    pub const fn new() -> Self {
+        Self::to(&SemihostingSink)
+    }
+
+    /// A formatter writing to `sink`.
+    pub const fn to(sink: &'static dyn Sink) -> Self {
+        Self {
+            buffer: [0; FORMATTER_BUFFER_SIZE],
+            len: 0,
+            sink,
+        }
+    }
+
+    /// Writes out the buffered output.
+    pub fn flush(&mut self) {
+        if self.len != 0 {
+            self.sink.write(&self.buffer[..self.len]);
+        }
+        self.len = 0;
+    }
+}
+
+impl Default for Formatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl core::fmt::Write for Formatter {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for &byte in s.as_bytes() {
+            self.buffer[self.len] = byte;
+            self.len += 1;
+            if byte == b'\n' || self.len == FORMATTER_BUFFER_SIZE {
+                self.flush();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Formatter {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+/// Writes every byte of `bytes` to stdout.
+fn write_all(bytes: &[u8]) {
+    let _ = semihosting::Stdout.write_all(bytes);
+}
+
+/// Ouputs a c string using arm semihostting calls.
+///
+/// This uses the [`SYS_WRITE0`] call and thus requires a C style zero
+/// terminated string [`CStr`]
+///
+/// [`CStr`]: https://doc.rust-lang.org/core/ffi/CStr/index.html
+pub fn putcs(s: &CStr) {
+    unsafe {
+        arm_asm!(
+            "movs r0, {syswrite0}",
+            "movs r1, {s}",
+            "bkpt {angel_swi}",
+            syswrite0 = const SYS_WRITE0,
+            s = in(reg) s.as_ptr(),
+            angel_swi = const ANGEL_SWI,
+            out("r0") _,
+            out("r1") _,
+        );
+    }
+}
+
+/// Outputs a string using arm semihostting calls.
+///
+/// This uses the `SYS_WRITE` call, a single one for the whole string
+/// unless the debugger writes it partially.
+///
+/// To output formatted text, prefer a [`Formatter`] which buffers
+/// the output up to each newline.
+pub fn puts(s: &str) {
+    write_all(s.as_bytes());
+}
+
+/// Ouputs a character byte using semihostting calls.
+///
+/// This uses the [`SYS_WRITEC`] call. Using this to output
+/// a string by looping on bytes would be *very* slow.
+pub fn putc(c: u8) {
+    unsafe {
+        arm_asm!(
+            "movs r0, {syswrite0}",
+            "movs r1, {c}",
+            "bkpt {angel_swi}",
+            syswrite0 = const SYS_WRITEC,
+            c = in(reg) core::ptr::addr_of!(c),
+            angel_swi = const ANGEL_SWI,
+            out("r0") _,
+            out("r1") _,
+        );
+    }
+}
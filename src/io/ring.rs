@@ -0,0 +1,271 @@
+//! An in-RAM ring keeping the latest output, to be read back later by the partition or from a
+//! memory dump.
+//!
+//! ```ignore
+//! static RING: RingSink<1024> = RingSink::new(); // The latest 1023 bytes
+//!
+//! pip_mpu::io::log::set_sink(&RING);
+//! // ...
+//! let mut buf = [0u8; 64];
+//! let count = RING.read(&mut buf);
+//! ```
+
+use crate::io::Sink;
+use crate::tools::SpinLock;
+
+/// The bytes a writer can add to a ring of `size` bytes. One byte always stays unused, so that a
+/// full ring is told apart from an empty one.
+pub fn ring_free(size: usize, write: usize, read: usize) -> usize {
+    if read > write {
+        read - write - 1
+    } else {
+        size - 1 - write + read
+    }
+}
+
+/// The bytes a reader can take from a ring of `size` bytes.
+pub fn ring_used(size: usize, write: usize, read: usize) -> usize {
+    if write >= read {
+        write - read
+    } else {
+        size - read + write
+    }
+}
+
+/// Copies as much of `bytes` as fits to the ring `buffer`, from `write`.
+///
+/// Returns the number of bytes copied and the new write offset.
+pub fn ring_write(buffer: &mut [u8], write: usize, read: usize, bytes: &[u8]) -> (usize, usize) {
+    let count = bytes.len().min(ring_free(buffer.len(), write, read));
+    let first = count.min(buffer.len() - write);
+    buffer[write..write + first].copy_from_slice(&bytes[..first]);
+    buffer[..count - first].copy_from_slice(&bytes[first..count]);
+    (count, (write + count) % buffer.len())
+}
+
+/// Copies as many bytes of the ring `buffer` as fit to `out`, from `read`.
+///
+/// Returns the number of bytes copied and the new read offset.
+pub fn ring_read(buffer: &[u8], write: usize, read: usize, out: &mut [u8]) -> (usize, usize) {
+    let count = out.len().min(ring_used(buffer.len(), write, read));
+    let first = count.min(buffer.len() - read);
+    out[..first].copy_from_slice(&buffer[read..read + first]);
+    out[first..count].copy_from_slice(&buffer[..count - first]);
+    (count, (read + count) % buffer.len())
+}
+
+/// A ring of `N` bytes keeping the latest `N - 1`, the oldest bytes being overwritten once it is
+/// full.
+pub struct Ring<const N: usize> {
+    buffer: [u8; N],
+    write: usize,       //Offset of the next byte pushed
+    read: usize,        //Offset of the oldest byte
+    overwritten: usize, //The bytes lost since the ring was created
+}
+
+impl<const N: usize> Ring<N> {
+    pub const fn new() -> Self {
+        Self {
+            buffer: [0; N],
+            write: 0,
+            read: 0,
+            overwritten: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        if N == 0 {
+            return 0;
+        }
+        ring_used(N, self.write, self.read)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn overwritten(&self) -> usize {
+        self.overwritten
+    }
+
+    /// Appends `bytes`, overwriting the oldest ones when the ring is full.
+    pub fn push(&mut self, mut bytes: &[u8]) {
+        let capacity = N.saturating_sub(1);
+        if bytes.len() > capacity {
+            self.overwritten += bytes.len() - capacity;
+            bytes = &bytes[bytes.len() - capacity..];
+        }
+        if bytes.is_empty() {
+            return;
+        }
+        let free = ring_free(N, self.write, self.read);
+        if free < bytes.len() {
+            let dropped = bytes.len() - free;
+            self.read = (self.read + dropped) % N;
+            self.overwritten += dropped;
+        }
+        (_, self.write) = ring_write(&mut self.buffer, self.write, self.read, bytes);
+    }
+
+    /// Moves the oldest bytes to `buf`, returning how many were moved.
+    pub fn pop(&mut self, buf: &mut [u8]) -> usize {
+        if N == 0 {
+            return 0;
+        }
+        let count;
+        (count, self.read) = ring_read(&self.buffer, self.write, self.read, buf);
+        count
+    }
+
+    pub fn clear(&mut self) {
+        self.read = self.write;
+    }
+}
+
+impl<const N: usize> Default for Ring<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A [`Sink`] writing to a [`Ring`], meant to be a `static`.
+pub struct RingSink<const N: usize> {
+    ring: SpinLock<Ring<N>>,
+}
+
+impl<const N: usize> RingSink<N> {
+    pub const fn new() -> Self {
+        Self {
+            ring: SpinLock::new(Ring::new()),
+        }
+    }
+
+    /// Moves the oldest bytes written to `buf`, returning how many were moved.
+    pub fn read(&self, buf: &mut [u8]) -> usize {
+        self.ring.lock(|ring| ring.pop(buf))
+    }
+
+    /// The bytes waiting to be read.
+    pub fn len(&self) -> usize {
+        self.ring.lock(|ring| ring.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The bytes lost because the ring was full.
+    pub fn overwritten(&self) -> usize {
+        self.ring.lock(|ring| ring.overwritten())
+    }
+}
+
+impl<const N: usize> Default for RingSink<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Sink for RingSink<N> {
+    fn write(&self, bytes: &[u8]) {
+        self.ring.lock(|ring| ring.push(bytes));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn free_and_used() {
+        // Empty, partly used, wrapped and full rings of 8 bytes.
+        for (write, read, used) in [(0, 0, 0), (5, 2, 3), (2, 6, 4), (7, 0, 7), (2, 3, 7)] {
+            assert_eq!(ring_used(8, write, read), used, "{write} {read}");
+            assert_eq!(ring_free(8, write, read), 7 - used, "{write} {read}");
+        }
+    }
+
+    #[test]
+    fn write_and_read_wrap_around() {
+        let mut buffer = [0; 8];
+        assert_eq!(ring_write(&mut buffer, 6, 3, b"abcd"), (4, 2));
+        assert_eq!(&buffer[6..], b"ab");
+        assert_eq!(&buffer[..2], b"cd");
+
+        // The read straddles the end of the buffer.
+        let mut out = [0; 8];
+        assert_eq!(ring_read(&buffer, 2, 6, &mut out), (4, 2));
+        assert_eq!(&out[..4], b"abcd");
+    }
+
+    #[test]
+    fn full_ring_takes_nothing() {
+        let mut buffer = [0; 8];
+        assert_eq!(ring_write(&mut buffer, 2, 3, b"x"), (0, 2));
+        assert_eq!(ring_write(&mut buffer, 0, 0, b"0123456789"), (7, 7));
+        assert_eq!(&buffer[..7], b"0123456");
+
+        let mut out = [0; 3];
+        assert_eq!(ring_read(&buffer, 7, 0, &mut out), (3, 3));
+        assert_eq!(&out, b"012");
+        assert_eq!(ring_read(&buffer, 3, 3, &mut out), (0, 3));
+    }
+
+    #[test]
+    fn ring_keeps_the_latest_bytes() {
+        let mut ring = Ring::<8>::new();
+        ring.push(b"abcde");
+        ring.push(b"fghij");
+        assert_eq!((ring.len(), ring.overwritten()), (7, 3));
+
+        let mut buf = [0; 8];
+        assert_eq!(ring.pop(&mut buf), 7);
+        assert_eq!(&buf[..7], b"defghij");
+        assert!(ring.is_empty());
+
+        ring.push(b"0123456789ab");
+        assert_eq!(ring.overwritten(), 8);
+        assert_eq!(ring.pop(&mut buf), 7);
+        assert_eq!(&buf[..7], b"56789ab");
+    }
+
+    #[test]
+    fn ring_pops_across_the_end() {
+        let mut ring = Ring::<8>::new();
+        let mut buf = [0; 8];
+        ring.push(b"012345");
+        assert_eq!(ring.pop(&mut buf[..4]), 4);
+        ring.push(b"6789");
+        assert_eq!(ring.overwritten(), 0);
+        assert_eq!(ring.pop(&mut buf), 6);
+        assert_eq!(&buf[..6], b"456789");
+
+        ring.push(b"xy");
+        ring.clear();
+        assert!(ring.is_empty());
+        assert_eq!(ring.pop(&mut buf), 0);
+    }
+
+    #[test]
+    fn tiny_rings_drop_everything() {
+        let mut empty = Ring::<0>::new();
+        empty.push(b"abc");
+        assert_eq!((empty.len(), empty.overwritten()), (0, 3));
+        assert_eq!(empty.pop(&mut [0; 4]), 0);
+
+        let mut single = Ring::<1>::new();
+        single.push(b"abc");
+        assert_eq!((single.len(), single.overwritten()), (0, 3));
+    }
+
+    #[test]
+    fn sink_writes_to_its_ring() {
+        let sink = RingSink::<16>::new();
+        sink.write(b"hello");
+        assert_eq!(sink.len(), 5);
+        let mut buf = [0; 16];
+        assert_eq!(sink.read(&mut buf), 5);
+        assert_eq!(&buf[..5], b"hello");
+        assert!(sink.is_empty());
+    }
+}
@@ -0,0 +1,353 @@
+//! An RTT (SEGGER Real Time Transfer) compatible channel in RAM, read and written by a debug probe
+//! or the simulator without halting the core.
+//!
+//! The control block starts with the id `"SEGGER RTT"`, which the probe looks for in RAM, then
+//! describes one up buffer, from the partition to the host, and one down buffer, from the host to
+//! the partition. Each buffer is a ring: the writer only moves its write offset, the reader only
+//! its read offset.
+//!
+//! ```ignore
+//! #[no_mangle]
+//! static _SEGGER_RTT: Rtt<1024, 16> = Rtt::new();
+//!
+//! _SEGGER_RTT.init();
+//! pip_mpu::io::log::set_sink(&_SEGGER_RTT);
+//! ```
+//!
+//! The control block is the first field of [`Rtt`], so probes locating it through the
+//! `_SEGGER_RTT` symbol find it at the static's address.
+
+use crate::io::ring::{ring_free, ring_read, ring_write};
+use crate::io::Sink;
+use crate::tools::SpinLock;
+use core::cell::UnsafeCell;
+use core::ptr;
+use core::sync::atomic::{fence, AtomicU32, Ordering};
+
+const RTT_ID: [u8; 16] = *b"SEGGER RTT\0\0\0\0\0\0";
+const MODE_MASK: u32 = 0b11;
+
+/// The name of both buffers, the one probes show for the default channel.
+const CHANNEL_NAME: &[u8] = b"Terminal\0";
+
+/// What a write does when the up buffer is full, as the host sees it in the channel flags.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RttMode {
+    NO_BLOCK_SKIP = 0, //Drops the whole write if it does not fit.
+    NO_BLOCK_TRIM = 1, //Writes what fits and drops the rest.
+    BLOCK_IF_FULL = 2, //Waits for the host to read, hangs without a probe.
+}
+
+/// A buffer of the control block, laid out as `SEGGER_RTT_BUFFER_UP` and `SEGGER_RTT_BUFFER_DOWN`.
+#[repr(C)]
+struct RttChannel {
+    name: *const u8,
+    buffer: *mut u8,
+    size: u32,
+    write: AtomicU32, //Moved by the writer only, the partition for an up buffer
+    read: AtomicU32,  //Moved by the reader only, the host for an up buffer
+    flags: AtomicU32,
+}
+
+#[cfg(target_pointer_width = "32")]
+const _: () = assert!(core::mem::size_of::<RttChannel>() == 24);
+
+impl RttChannel {
+    const fn empty() -> Self {
+        Self {
+            name: ptr::null(),
+            buffer: ptr::null_mut(),
+            size: 0,
+            write: AtomicU32::new(0),
+            read: AtomicU32::new(0),
+            flags: AtomicU32::new(0),
+        }
+    }
+
+    fn mode(&self) -> RttMode {
+        match self.flags.load(Ordering::Relaxed) & MODE_MASK {
+            0 => RttMode::NO_BLOCK_SKIP,
+            1 => RttMode::NO_BLOCK_TRIM,
+            _ => RttMode::BLOCK_IF_FULL,
+        }
+    }
+
+    /// Writes `bytes` to the ring as the channel's mode tells, returning how many were written.
+    /// Nothing is written while an offset lies outside of the ring.
+    fn write(&self, mut bytes: &[u8]) -> usize {
+        if self.buffer.is_null() || self.size == 0 {
+            return 0;
+        }
+        // The host only reads the bytes between the read and write offsets, which this writes
+        // around.
+        let ring = unsafe { core::slice::from_raw_parts_mut(self.buffer, self.size as usize) };
+        let mode = self.mode();
+        let mut written = 0;
+        loop {
+            let write = self.write.load(Ordering::Relaxed) as usize;
+            let read = self.read.load(Ordering::Acquire) as usize;
+            // The probe can leave any value in the offsets, the ring can not be written past them.
+            if write >= ring.len() || read >= ring.len() {
+                return written;
+            }
+            if mode == RttMode::NO_BLOCK_SKIP && ring_free(ring.len(), write, read) < bytes.len() {
+                return 0;
+            }
+            let (count, write) = ring_write(ring, write, read, bytes);
+            // The bytes must be in the ring before the host sees the new offset.
+            self.write.store(write as u32, Ordering::Release);
+            written += count;
+            bytes = &bytes[count..];
+            if bytes.is_empty() || mode != RttMode::BLOCK_IF_FULL {
+                return written;
+            }
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Moves the bytes the host wrote to `buf`, returning how many were moved. Nothing is moved
+    /// while an offset lies outside of the ring.
+    fn read(&self, buf: &mut [u8]) -> usize {
+        if self.buffer.is_null() || self.size == 0 {
+            return 0;
+        }
+        let ring = unsafe { core::slice::from_raw_parts(self.buffer, self.size as usize) };
+        let write = self.write.load(Ordering::Acquire) as usize;
+        let read = self.read.load(Ordering::Relaxed) as usize;
+        if write >= ring.len() || read >= ring.len() {
+            return 0;
+        }
+        let (count, read) = ring_read(ring, write, read, buf);
+        self.read.store(read as u32, Ordering::Release);
+        count
+    }
+}
+
+/// The control block, laid out as `SEGGER_RTT_CB` with one up and one down buffer.
+#[repr(C)]
+struct ControlBlock {
+    id: [u8; 16],
+    max_up: i32,
+    max_down: i32,
+    up: RttChannel,
+    down: RttChannel,
+}
+
+/// An RTT control block with an up buffer of `UP` bytes and a down buffer of `DOWN` bytes, meant to
+/// be a `static` in the partition's RAM.
+#[repr(C)]
+pub struct Rtt<const UP: usize, const DOWN: usize> {
+    control: SpinLock<ControlBlock>, //Held by the partition's writers, the host only sees the offsets
+    up_buffer: UnsafeCell<[u8; UP]>,
+    down_buffer: UnsafeCell<[u8; DOWN]>,
+}
+
+// The channels point to the buffers of the same `Rtt`.
+unsafe impl Send for ControlBlock {}
+
+// The buffers are only reached through the channels with the control block's lock held, and the
+// host only moves its own offsets.
+unsafe impl<const UP: usize, const DOWN: usize> Sync for Rtt<UP, DOWN> {}
+
+impl<const UP: usize, const DOWN: usize> Rtt<UP, DOWN> {
+    pub const fn new() -> Self {
+        Self {
+            control: SpinLock::new(ControlBlock {
+                id: [0; 16],
+                max_up: 1,
+                max_down: 1,
+                up: RttChannel::empty(),
+                down: RttChannel::empty(),
+            }),
+            up_buffer: UnsafeCell::new([0; UP]),
+            down_buffer: UnsafeCell::new([0; DOWN]),
+        }
+    }
+
+    /// Sets up the buffers, empty, then writes the id so that the probe finds a complete control
+    /// block. Up writes trim what does not fit.
+    pub fn init(&self) {
+        self.control.lock(|control| {
+            control.id = [0; 16];
+            control.up = RttChannel {
+                name: CHANNEL_NAME.as_ptr(),
+                buffer: self.up_buffer.get() as *mut u8,
+                size: UP as u32,
+                write: AtomicU32::new(0),
+                read: AtomicU32::new(0),
+                flags: AtomicU32::new(RttMode::NO_BLOCK_TRIM as u32),
+            };
+            control.down = RttChannel {
+                name: CHANNEL_NAME.as_ptr(),
+                buffer: self.down_buffer.get() as *mut u8,
+                size: DOWN as u32,
+                write: AtomicU32::new(0),
+                read: AtomicU32::new(0),
+                flags: AtomicU32::new(RttMode::NO_BLOCK_SKIP as u32),
+            };
+            fence(Ordering::SeqCst);
+            unsafe { ptr::write_volatile(&mut control.id, RTT_ID) };
+        })
+    }
+
+    /// Changes what writes do once the up buffer is full.
+    pub fn set_mode(&self, mode: RttMode) {
+        self.control
+            .lock(|control| control.up.flags.store(mode as u32, Ordering::Relaxed))
+    }
+
+    /// Writes `bytes` to the up buffer, returning how many were written. Nothing is written before
+    /// [`Rtt::init`].
+    pub fn write(&self, bytes: &[u8]) -> usize {
+        self.control.lock(|control| control.up.write(bytes))
+    }
+
+    /// Moves the bytes the host wrote to the down buffer to `buf`, returning how many were moved.
+    pub fn read(&self, buf: &mut [u8]) -> usize {
+        self.control.lock(|control| control.down.read(buf))
+    }
+}
+
+impl<const UP: usize, const DOWN: usize> Default for Rtt<UP, DOWN> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const UP: usize, const DOWN: usize> Sink for Rtt<UP, DOWN> {
+    fn write(&self, bytes: &[u8]) {
+        Rtt::write(self, bytes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Takes what the partition wrote to the up buffer, as the probe does.
+    fn host_read<const UP: usize, const DOWN: usize>(rtt: &Rtt<UP, DOWN>, len: usize) -> Vec<u8> {
+        rtt.control.lock(|control| {
+            let up = &control.up;
+            let ring = unsafe { core::slice::from_raw_parts(up.buffer, up.size as usize) };
+            let mut out = vec![0; len];
+            let (count, read) = ring_read(
+                ring,
+                up.write.load(Ordering::Relaxed) as usize,
+                up.read.load(Ordering::Relaxed) as usize,
+                &mut out,
+            );
+            up.read.store(read as u32, Ordering::Relaxed);
+            out.truncate(count);
+            out
+        })
+    }
+
+    /// Writes `bytes` to the down buffer, as the probe does.
+    fn host_write<const UP: usize, const DOWN: usize>(rtt: &Rtt<UP, DOWN>, bytes: &[u8]) -> usize {
+        rtt.control.lock(|control| {
+            let down = &control.down;
+            let ring = unsafe { core::slice::from_raw_parts_mut(down.buffer, down.size as usize) };
+            let (count, write) = ring_write(
+                ring,
+                down.write.load(Ordering::Relaxed) as usize,
+                down.read.load(Ordering::Relaxed) as usize,
+                bytes,
+            );
+            down.write.store(write as u32, Ordering::Relaxed);
+            count
+        })
+    }
+
+    /// An initialized control block, boxed so that its channels keep pointing to its buffers.
+    fn rtt() -> Box<Rtt<8, 8>> {
+        let rtt = Box::new(Rtt::new());
+        rtt.init();
+        rtt
+    }
+
+    #[test]
+    fn init_writes_the_id_last() {
+        let rtt = Box::new(Rtt::<8, 8>::new());
+        assert_eq!(rtt.write(b"lost"), 0);
+        rtt.control.lock(|control| assert_eq!(control.id, [0; 16]));
+        rtt.init();
+        rtt.control.lock(|control| {
+            assert_eq!(control.id, RTT_ID);
+            assert_eq!(control.up.mode(), RttMode::NO_BLOCK_TRIM);
+            assert_eq!(control.down.mode(), RttMode::NO_BLOCK_SKIP);
+        });
+    }
+
+    #[test]
+    fn control_block_is_at_the_static_address() {
+        let rtt = rtt();
+        let address = &*rtt as *const Rtt<8, 8> as *const u8;
+        assert_eq!(unsafe { *(address as *const [u8; 16]) }, RTT_ID);
+    }
+
+    #[test]
+    fn trim_writes_what_fits() {
+        let rtt = rtt();
+        assert_eq!(rtt.write(b"0123456789"), 7);
+        assert_eq!(rtt.write(b"x"), 0);
+        assert_eq!(host_read(&*rtt, 16), b"0123456");
+    }
+
+    #[test]
+    fn skip_drops_the_whole_write() {
+        let rtt = rtt();
+        rtt.set_mode(RttMode::NO_BLOCK_SKIP);
+        assert_eq!(rtt.write(b"0123456789"), 0);
+        assert_eq!(rtt.write(b"01234"), 5);
+        assert_eq!(rtt.write(b"567"), 0);
+        assert_eq!(rtt.write(b"56"), 2);
+        assert_eq!(host_read(&*rtt, 16), b"0123456");
+    }
+
+    #[test]
+    fn writes_wrap_around() {
+        let rtt = rtt();
+        assert_eq!(rtt.write(b"012345"), 6);
+        assert_eq!(host_read(&*rtt, 4), b"0123");
+        // 2 bytes to the end of the buffer, 3 from its start.
+        assert_eq!(rtt.write(b"abcde"), 5);
+        assert_eq!(host_read(&*rtt, 16), b"45abcde");
+        assert_eq!(rtt.write(b"fghijklm"), 7);
+        assert_eq!(host_read(&*rtt, 16), b"fghijkl");
+    }
+
+    #[test]
+    fn reads_straddle_the_end_of_the_down_buffer() {
+        let rtt = rtt();
+        let mut buf = [0; 8];
+        assert_eq!(host_write(&*rtt, b"012345"), 6);
+        assert_eq!(rtt.read(&mut buf[..5]), 5);
+        assert_eq!(&buf[..5], b"01234");
+
+        assert_eq!(host_write(&*rtt, b"abcdefgh"), 6);
+        assert_eq!(rtt.read(&mut buf), 7);
+        assert_eq!(&buf[..7], b"5abcdef");
+        assert_eq!(rtt.read(&mut buf), 0);
+    }
+
+    #[test]
+    fn offsets_outside_of_the_buffers_move_nothing() {
+        let rtt = rtt();
+        let mut buf = [0; 8];
+        rtt.control.lock(|control| {
+            control.up.read.store(8, Ordering::Relaxed);
+            control.down.write.store(100, Ordering::Relaxed);
+        });
+        assert_eq!(rtt.write(b"0123"), 0);
+        assert_eq!(rtt.read(&mut buf), 0);
+
+        rtt.control.lock(|control| {
+            control.up.read.store(0, Ordering::Relaxed);
+            control.down.write.store(0, Ordering::Relaxed);
+            control.down.read.store(u32::MAX, Ordering::Relaxed);
+        });
+        assert_eq!(rtt.write(b"0123"), 4);
+        assert_eq!(host_read(&*rtt, 16), b"0123");
+        assert_eq!(rtt.read(&mut buf), 0);
+    }
+}
@@ -0,0 +1,244 @@
+//! ARM semihosting calls, served by the debugger: files of the host, clock, command line and exit.
+//!
+//! Every call stops the core until the debugger answers, they are meant for development, such as
+//! loading a child image from the host or reporting the result of a test.
+
+// Off ARM the semihosting calls only panic, leaving their arguments and constants unused.
+#![cfg_attr(
+    not(target_arch = "arm"),
+    allow(dead_code, unused_variables, unreachable_code)
+)]
+
+use core::ffi::CStr;
+
+const ANGEL_SWI: u8 = 0xab;
+
+const SYS_OPEN: u32 = 0x01;
+const SYS_CLOSE: u32 = 0x02;
+const SYS_WRITE: u32 = 0x05;
+const SYS_READ: u32 = 0x06;
+const SYS_SEEK: u32 = 0x0a;
+const SYS_FLEN: u32 = 0x0c;
+const SYS_CLOCK: u32 = 0x10;
+const SYS_TIME: u32 = 0x11;
+const SYS_ERRNO: u32 = 0x13;
+const SYS_GET_CMDLINE: u32 = 0x15;
+const SYS_EXIT: u32 = 0x18;
+const SYS_EXIT_EXTENDED: u32 = 0x20;
+
+/// The semihosting handle of the debugger's stdout.
+const STDOUT: u32 = 1;
+
+/// Issues the semihosting call `operation`, `parameter` being its argument or the address of its
+/// parameter block.
+///
+/// # Safety
+///
+/// `parameter` must be what `operation` expects, and the memory it points to must stay valid for
+/// the call.
+unsafe fn call(operation: u32, parameter: usize) -> u32 {
+    let result: u32;
+    arm_asm!(
+        "bkpt {angel_swi}",
+        angel_swi = const ANGEL_SWI,
+        inout("r0") operation => result,
+        inout("r1") parameter => _,
+        options(nostack),
+    );
+    result
+}
+
+/// The error number of the host, as given by `SYS_ERRNO` after a failed call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Errno(pub i32);
+
+/// The error number of the last failed call.
+pub fn errno() -> Errno {
+    Errno(unsafe { call(SYS_ERRNO, 0) } as i32)
+}
+
+/// The result of a call returning -1 on failure.
+fn check(result: u32) -> Result<u32, Errno> {
+    if result as i32 == -1 {
+        Err(errno())
+    } else {
+        Ok(result)
+    }
+}
+
+/// Writes up to `bytes.len()` bytes to `handle`, returning the number of bytes written.
+fn write(handle: u32, bytes: &[u8]) -> Result<usize, Errno> {
+    let block: [u32; 3] = [handle, bytes.as_ptr() as u32, bytes.len() as u32];
+    let not_written = unsafe { call(SYS_WRITE, block.as_ptr() as usize) } as usize;
+    if not_written > bytes.len() || (not_written == bytes.len() && !bytes.is_empty()) {
+        return Err(errno());
+    }
+    Ok(bytes.len() - not_written)
+}
+
+/// Writes every byte of `bytes` to `handle`, as long as the host makes progress.
+fn write_all(handle: u32, mut bytes: &[u8]) -> Result<(), Errno> {
+    while !bytes.is_empty() {
+        let written = write(handle, bytes)?;
+        bytes = &bytes[written..];
+    }
+    Ok(())
+}
+
+/// The debugger's stdout. Unlike a `File`, it is never closed, the host owning it.
+#[derive(Clone, Copy, Debug)]
+pub struct Stdout;
+
+impl Stdout {
+    /// Writes up to `bytes.len()` bytes, returning the number of bytes written.
+    pub fn write(&mut self, bytes: &[u8]) -> Result<usize, Errno> {
+        write(STDOUT, bytes)
+    }
+
+    /// Writes every byte of `bytes`, as long as the host makes progress.
+    pub fn write_all(&mut self, bytes: &[u8]) -> Result<(), Errno> {
+        write_all(STDOUT, bytes)
+    }
+}
+
+/// How `File::open` opens a file, as the `fopen` mode of the same name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpenMode {
+    R = 0,    //"r"
+    RB = 1,   //"rb"
+    RP = 2,   //"r+"
+    RPB = 3,  //"r+b"
+    W = 4,    //"w"
+    WB = 5,   //"wb"
+    WP = 6,   //"w+"
+    WPB = 7,  //"w+b"
+    A = 8,    //"a"
+    AB = 9,   //"ab"
+    AP = 10,  //"a+"
+    APB = 11, //"a+b"
+}
+
+/// A file of the host, open until `close` gives its handle back.
+#[derive(Debug)]
+pub struct File {
+    handle: u32,
+}
+
+impl File {
+    /// The file `name` of the host, `":tt"` standing for the debugger's console.
+    pub fn open(name: &CStr, mode: OpenMode) -> Result<File, Errno> {
+        let block: [u32; 3] = [
+            name.as_ptr() as u32,
+            mode as u32,
+            name.to_bytes().len() as u32,
+        ];
+        let handle = check(unsafe { call(SYS_OPEN, block.as_ptr() as usize) })?;
+        Ok(File { handle })
+    }
+
+    pub fn close(self) -> Result<(), Errno> {
+        check(unsafe { call(SYS_CLOSE, core::ptr::addr_of!(self.handle) as usize) }).map(|_| ())
+    }
+
+    /// Reads up to `buf.len()` bytes, returning the number of bytes read, 0 at the end of the file.
+    pub fn read(&mut self, buf: &mut [u8]) -> Result<usize, Errno> {
+        let block: [u32; 3] = [self.handle, buf.as_mut_ptr() as u32, buf.len() as u32];
+        let not_read = unsafe { call(SYS_READ, block.as_ptr() as usize) } as usize;
+        if not_read > buf.len() {
+            return Err(errno());
+        }
+        Ok(buf.len() - not_read)
+    }
+
+    /// Reads until `buf` is full or the file ends, returning the number of bytes read.
+    pub fn read_all(&mut self, buf: &mut [u8]) -> Result<usize, Errno> {
+        let mut count = 0;
+        while count < buf.len() {
+            match self.read(&mut buf[count..])? {
+                0 => break,
+                read => count += read,
+            }
+        }
+        Ok(count)
+    }
+
+    /// Writes up to `bytes.len()` bytes, returning the number of bytes written.
+    pub fn write(&mut self, bytes: &[u8]) -> Result<usize, Errno> {
+        write(self.handle, bytes)
+    }
+
+    /// Writes every byte of `bytes`, as long as the host makes progress.
+    pub fn write_all(&mut self, bytes: &[u8]) -> Result<(), Errno> {
+        write_all(self.handle, bytes)
+    }
+
+    /// Moves to `position` bytes from the start of the file.
+    pub fn seek(&mut self, position: usize) -> Result<(), Errno> {
+        let block: [u32; 2] = [self.handle, position as u32];
+        let result = unsafe { call(SYS_SEEK, block.as_ptr() as usize) };
+        if (result as i32) < 0 {
+            return Err(errno());
+        }
+        Ok(())
+    }
+
+    /// The length of the file in bytes.
+    pub fn len(&self) -> Result<usize, Errno> {
+        let block: [u32; 1] = [self.handle];
+        check(unsafe { call(SYS_FLEN, block.as_ptr() as usize) }).map(|len| len as usize)
+    }
+
+    pub fn is_empty(&self) -> Result<bool, Errno> {
+        self.len().map(|len| len == 0)
+    }
+}
+
+/// The centiseconds elapsed since the execution started.
+pub fn clock() -> Result<u32, Errno> {
+    check(unsafe { call(SYS_CLOCK, 0) })
+}
+
+/// The seconds elapsed since 00:00 January 1, 1970, as told by the host.
+pub fn time() -> u32 {
+    unsafe { call(SYS_TIME, 0) }
+}
+
+/// The command line the debugger was given for the program, written in `buf`.
+pub fn cmdline(buf: &mut [u8]) -> Result<&[u8], Errno> {
+    let mut block: [u32; 2] = [buf.as_mut_ptr() as u32, buf.len() as u32];
+    if unsafe { call(SYS_GET_CMDLINE, block.as_mut_ptr() as usize) } != 0 {
+        return Err(errno());
+    }
+    Ok(&buf[..(block[1] as usize).min(buf.len())])
+}
+
+/// Why the program stops, as told to the debugger by `exit`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExitReason {
+    APPLICATION_EXIT = 0x20026,       //The program ended normally.
+    RUN_TIME_ERROR_UNKNOWN = 0x20023, //The program failed.
+    INTERNAL_ERROR = 0x20024,         //The program detected an internal error.
+}
+
+/// Stops the program, reporting `reason` to the debugger.
+pub fn exit(reason: ExitReason) -> ! {
+    unsafe {
+        call(SYS_EXIT, reason as usize);
+    }
+    loop {
+        core::hint::spin_loop();
+    }
+}
+
+/// Stops the program with the exit code `code`, 0 meaning success, such as the result of a test.
+///
+/// Debuggers without `SYS_EXIT_EXTENDED` stop anyway, without the code.
+pub fn exit_extended(code: u32) -> ! {
+    let block: [u32; 2] = [ExitReason::APPLICATION_EXIT as u32, code];
+    unsafe {
+        call(SYS_EXIT_EXTENDED, block.as_ptr() as usize);
+    }
+    loop {
+        core::hint::spin_loop();
+    }
+}
@@ -0,0 +1,46 @@
+#![doc = include_str!("../README.md")]
+#![cfg_attr(not(test), no_std)]
+// Unit variants are written in SCREAMING_CASE, as the constants of pip's C headers.
+#![allow(non_camel_case_types)]
+// Failures carrying no detail are `Err(())`, and consumed linear handles are given back as errors.
+#![allow(clippy::result_unit_err)]
+// The system calls take pip's arguments one by one.
+#![allow(clippy::too_many_arguments)]
+// A directory is named after its main module, such as `boot_manifest::boot_manifest`.
+#![allow(clippy::module_inception)]
+// Argument lists align their descriptions on a column.
+#![allow(clippy::doc_overindented_list_items)]
+
+/// `core::arch::asm!` on ARM. Elsewhere, such as in host tests, the code around it builds but
+/// panics where the instructions would run.
+macro_rules! arm_asm {
+    ($($args:tt)*) => {
+        #[cfg(target_arch = "arm")]
+        core::arch::asm!($($args)*);
+        #[cfg(not(target_arch = "arm"))]
+        $crate::tools::not_arm();
+    };
+}
+
+pub mod board;
+pub mod core;
+pub mod rust;
+pub mod service;
+pub mod tools;
+
+#[cfg(feature = "child")]
+pub mod child;
+
+#[cfg(feature = "alloc")]
+pub mod allocator;
+
+#[cfg(feature = "manage-partition")]
+pub mod config;
+#[cfg(feature = "manage-partition")]
+pub mod manage_partition;
+
+#[cfg(feature = "boot")]
+pub mod boot_manifest;
+
+#[cfg(feature = "io")]
+pub mod io;
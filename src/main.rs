@@ -1,59 +1,76 @@
-#![doc = include_str!("../README.md")]
-#![no_std]
-#![no_main]
-#![feature(asm_const)]
+//! The root partition: boots the children described by the boot manifest.
+//!
+//! It only runs on ARMv7-M. Built for the host, such as by `cargo build --workspace`, the binary
+//! only says so.
+#![cfg_attr(target_arch = "arm", no_std, no_main)]
+// The panic handlers are the only users of some imports, and are ARM only.
+#![cfg_attr(not(target_arch = "arm"), allow(unused_imports))]
 
-mod pip_mpu;
+#[cfg(feature = "child")]
+compile_error!(
+    "the child feature brings the panic handler of child partitions, build the root without it"
+);
 
-pub mod io;
-use core::cell::Cell;
-use core::ffi::CStr;
+use core::fmt::Write;
 use mini_format::*;
 
-use io::{putc, putcs, puts};
+use pip_mpu::allocator::PartitionAllocator;
+use pip_mpu::board::MemoryRange;
+use pip_mpu::boot_manifest::boot_manifest::{m_boot_children, BootError};
+use pip_mpu::boot_manifest::manifest_items::Manifest;
+use pip_mpu::core::pip_items::{ExtendedContext, Interface};
+use pip_mpu::io::semihosting::{self, ExitReason};
+use pip_mpu::io::{log, putc, puts, Formatter};
+use pip_mpu::manage_partition::memory_report::MemoryReport;
+
+#[cfg_attr(target_arch = "arm", global_allocator)]
+static ALLOCATOR: PartitionAllocator = PartitionAllocator::new();
 
-use crate::pip_mpu::core::pip_items::{BasicContext, Interface};
-use crate::pip_mpu::manage_partition::manage_partition::{
-    m_create_partition, m_delete_partition, m_map_partition,
-};
-use crate::pip_mpu::rust::pip_rust_items::{Block, BlockId};
-use crate::pip_mpu::rust::pip_rust_mpu::find_block;
-use core::fmt::Write;
 #[no_mangle]
 extern "C" fn start(interface: &Interface) -> ! {
     bkpt();
-    let root_pd_block_id = BlockId::new(interface.part_desc_block_id as usize);
-    let root_ctx: BasicContext = Default::default();
-    let block_0 = find_block(
-        &root_pd_block_id,
-        (interface.unused_ram_start as *const u32),
-    )
-    .unwrap();
+    log::set_partition("root");
+    if let Err(error) = interface.validate() {
+        pip_mpu::error!("malformed interface: {error}");
+        let _ = write!(Formatter::new(), "{interface}");
+        semihosting::exit(ExitReason::RUN_TIME_ERROR_UNKNOWN);
+    }
+    let root_ctx: ExtendedContext = Default::default();
 
-    let size = ((block_0.end_addr as u32) - (block_0.start_addr as u32)) as usize;
+    let children = Manifest::find(interface)
+        .map_err(BootError::Manifest)
+        .and_then(|manifest| {
+            m_boot_children(interface, &root_ctx as *const ExtendedContext, &manifest)
+        })
+        .unwrap_or_else(|error| {
+            pip_mpu::error!("boot failed: {error}");
+            semihosting::exit(ExitReason::RUN_TIME_ERROR_UNKNOWN)
+        });
+    pip_mpu::info!("{} children booted", children.count);
 
-    let partition_result = m_create_partition(
-        interface,
-        &root_ctx as *const BasicContext,
-        &block_0,
-        None,
-        interface.unused_rom_start,
-        512,
-        512,
-        512,
-        0,
-    )
-    .unwrap();
+    // The RAM left once the children are booted is the root's heap.
+    ALLOCATOR.init(MemoryRange {
+        start: children.free_ram_start as usize,
+        size: (interface.ram_end as usize).saturating_sub(children.free_ram_start as usize),
+    });
 
-    m_map_partition(&partition_result);
-    let delete_result = m_delete_partition(&partition_result);
+    let report = MemoryReport::new(
+        interface,
+        |block_id| children.owns(block_id),
+        &children.block_starts(),
+    );
+    let _ = write!(Formatter::new(), "{report}");
 
-    loop {}
+    loop {
+        core::hint::spin_loop();
+    }
 }
 
+#[cfg(target_arch = "arm")]
 const BKPT: u8 = 0x3;
 
 pub fn bkpt() {
+    #[cfg(target_arch = "arm")]
     unsafe {
         core::arch::asm!("bkpt {number}",
         number = const BKPT
@@ -61,19 +78,29 @@ pub fn bkpt() {
     }
 }
 
-#[cfg(not(debug_assertions))]
+#[cfg(not(target_arch = "arm"))]
+fn main() {
+    eprintln!("the root partition only runs on ARMv7-M, build it with `cargo make`");
+    std::process::exit(1);
+}
+
+#[cfg(all(target_arch = "arm", not(debug_assertions)))]
 #[panic_handler]
 fn panic(_: &core::panic::PanicInfo) -> ! {
     puts("\n\n\nPANIC\n");
-    loop {}
+    loop {
+        core::hint::spin_loop();
+    }
 }
 
-#[cfg(debug_assertions)]
+#[cfg(all(target_arch = "arm", debug_assertions))]
 #[panic_handler]
 fn panic(panic_info: &core::panic::PanicInfo) -> ! {
     puts("\n\n\nPANIC\n\n\n");
     if let Some(location) = panic_info.location() {
         format_dec(location.line(), |c| putc(c as u8));
     }
-    loop {}
+    loop {
+        core::hint::spin_loop();
+    }
 }
@@ -0,0 +1,449 @@
+use crate::rust::pip_rust_items::{AccessRights, Block, MemBlockId};
+use crate::rust::pip_rust_mpu;
+
+/*
+    The buddy allocator hands out pip blocks an MPU region can cover as is: their size is a power
+    of two, and their start address is aligned on their size.
+
+    It takes the largest such area out of the parent's free RAM block, and cuts it in halves with
+    cut_memory_block until the requested size is reached. A freed block is merged back with its
+    buddy (the other half of the block they were cut from) with merge_memory_blocks as long as the
+    buddy is free too, so that creating and deleting children does not fragment the RAM.
+
++-----------------------------+ <- base
+|            free             |    order max_order - 1
+|                             |
++-----------------------------+
+|      child 1 (allocated)    |    order max_order - 2
++--------------+--------------+
+|     free     |   child 2    |    order max_order - 3
++--------------+--------------+ <- base + 2^max_order
+*/
+
+/// The smallest block, and region, the ARMv7-M MPU can handle is 32 bytes long.
+pub const MIN_BLOCK_ORDER: u32 = 5;
+
+/// Every block the allocator knows of, free or not, takes an entry.
+pub const MAX_BUDDY_BLOCKS: usize = 32;
+
+/// The pip calls the allocator cuts and merges its blocks with, `PipBlocks` outside of tests.
+pub trait BlockCutter {
+    /// Cuts `block_id` at `addr`, see `pip_rust_mpu::cut_memory_block`.
+    fn cut(
+        &mut self,
+        block_id: MemBlockId,
+        addr: usize,
+    ) -> Result<(MemBlockId, MemBlockId), MemBlockId>;
+    /// Merges `upper_id` back into `lower_id`, see `pip_rust_mpu::merge_memory_blocks`.
+    fn merge(
+        &mut self,
+        lower_id: MemBlockId,
+        upper_id: MemBlockId,
+    ) -> Result<MemBlockId, (MemBlockId, MemBlockId)>;
+}
+
+/// Cuts and merges the blocks of the current partition through pip.
+pub struct PipBlocks;
+
+impl BlockCutter for PipBlocks {
+    fn cut(
+        &mut self,
+        block_id: MemBlockId,
+        addr: usize,
+    ) -> Result<(MemBlockId, MemBlockId), MemBlockId> {
+        pip_rust_mpu::cut_memory_block(block_id, addr as *const u32, None)
+    }
+
+    fn merge(
+        &mut self,
+        lower_id: MemBlockId,
+        upper_id: MemBlockId,
+    ) -> Result<MemBlockId, (MemBlockId, MemBlockId)> {
+        pip_rust_mpu::merge_memory_blocks(lower_id, upper_id, None)
+    }
+}
+
+#[derive(Debug)]
+pub enum BuddyError {
+    TooSmall,      //The given block cannot hold an aligned block of the minimum size.
+    OutOfMemory,   //No free block is large enough.
+    TooManyBlocks, //Splitting would exceed MAX_BUDDY_BLOCKS.
+    UnknownBlock,  //The block to free was not allocated by this allocator.
+    Pip,           //A pip system call failed.
+}
+
+struct BuddyBlock {
+    id: usize,                  //Local id of the block, to recognize it when it is freed.
+    handle: Option<MemBlockId>, //The handle on the block while it is free, None while it is allocated.
+    start: usize,
+    order: u32,
+}
+
+impl BuddyBlock {
+    fn new(handle: MemBlockId, start: usize, order: u32) -> Self {
+        Self {
+            id: handle.id(),
+            handle: Some(handle),
+            start,
+            order,
+        }
+    }
+
+    fn size(&self) -> usize {
+        1 << self.order
+    }
+
+    fn is_free(&self) -> bool {
+        self.handle.is_some()
+    }
+}
+
+pub struct BuddyAllocator<C: BlockCutter = PipBlocks> {
+    cutter: C,
+    blocks: [Option<BuddyBlock>; MAX_BUDDY_BLOCKS],
+    base: usize,    //Start address of the managed area, aligned on its size.
+    max_order: u32, //The managed area is 2^max_order bytes long.
+    rights: AccessRights,
+    pub head_block_id: Option<MemBlockId>, //The part of the given block before the managed area, left to the parent.
+    pub tail_block_id: Option<MemBlockId>, //The part of the given block after the managed area, left to the parent.
+}
+
+/// The smallest order whose block holds `size` bytes.
+fn order_of(size: usize) -> u32 {
+    size.max(1).next_power_of_two().trailing_zeros()
+}
+
+impl BuddyAllocator {
+    /// Takes the largest naturally aligned power-of-two area out of `block`, one of the
+    /// partition's free RAM blocks. The parts of `block` around this area are cut and left to
+    /// the partition, see `head_block_id` and `tail_block_id`.
+    pub fn new(block: Block) -> Result<Self, BuddyError> {
+        Self::with_cutter(block, PipBlocks)
+    }
+}
+
+impl<C: BlockCutter> BuddyAllocator<C> {
+    /// `new`, cutting and merging the blocks with `cutter`.
+    pub fn with_cutter(block: Block, mut cutter: C) -> Result<Self, BuddyError> {
+        let start = block.start_addr as usize;
+        let end = block.end();
+        let size = end.checked_sub(start).ok_or(BuddyError::TooSmall)?;
+        if size < 1 << MIN_BLOCK_ORDER {
+            return Err(BuddyError::TooSmall);
+        }
+
+        let (base, max_order) = (MIN_BLOCK_ORDER..=size.ilog2())
+            .rev()
+            .find_map(|order| {
+                let base = (start + (1 << order) - 1) & !((1 << order) - 1);
+                (base + (1 << order) <= end).then_some((base, order))
+            })
+            .ok_or(BuddyError::TooSmall)?;
+
+        let (head_block_id, area_id) = if base > start {
+            let (head_block_id, area_id) = cutter
+                .cut(block.local_id, base)
+                .map_err(|_| BuddyError::Pip)?;
+            (Some(head_block_id), area_id)
+        } else {
+            (None, block.local_id)
+        };
+        let area_end = base + (1 << max_order);
+        let (area_id, tail_block_id) = if area_end < end {
+            let (area_id, tail_block_id) =
+                cutter.cut(area_id, area_end).map_err(|_| BuddyError::Pip)?;
+            (area_id, Some(tail_block_id))
+        } else {
+            (area_id, None)
+        };
+
+        let mut blocks: [Option<BuddyBlock>; MAX_BUDDY_BLOCKS] = core::array::from_fn(|_| None);
+        blocks[0] = Some(BuddyBlock::new(area_id, base, max_order));
+        Ok(BuddyAllocator {
+            cutter,
+            blocks,
+            base,
+            max_order,
+            rights: block.rights,
+            head_block_id,
+            tail_block_id,
+        })
+    }
+
+    /// Allocates a block of at least `size` bytes, aligned on its own size.
+    pub fn alloc(&mut self, size: usize) -> Result<Block, BuddyError> {
+        let order = order_of(size).max(MIN_BLOCK_ORDER);
+        if order > self.max_order {
+            return Err(BuddyError::OutOfMemory);
+        }
+
+        // The smallest free block large enough
+        let index = (0..MAX_BUDDY_BLOCKS)
+            .filter(|i| matches!(&self.blocks[*i], Some(b) if b.is_free() && b.order >= order))
+            .min_by_key(|i| self.blocks[*i].as_ref().unwrap().order)
+            .ok_or(BuddyError::OutOfMemory)?;
+
+        let splits = (self.blocks[index].as_ref().unwrap().order - order) as usize;
+        if self.blocks.iter().filter(|b| b.is_none()).count() < splits {
+            return Err(BuddyError::TooManyBlocks);
+        }
+
+        // Cut the block in halves, keeping the lower one, until it has the requested order
+        while self.blocks[index].as_ref().unwrap().order > order {
+            let block = self.blocks[index].as_mut().unwrap();
+            let upper_start = block.start + block.size() / 2;
+            let handle = block.handle.take().unwrap();
+            let (lower_id, upper_id) =
+                self.cutter.cut(handle, upper_start).map_err(|block_id| {
+                    block.handle = Some(block_id);
+                    BuddyError::Pip
+                })?;
+            block.handle = Some(lower_id);
+            block.order -= 1;
+            let upper = BuddyBlock::new(upper_id, upper_start, block.order);
+            let slot = self.blocks.iter().position(|b| b.is_none()).unwrap();
+            self.blocks[slot] = Some(upper);
+        }
+
+        let block = self.blocks[index].as_mut().unwrap();
+        Ok(Block {
+            local_id: block.handle.take().unwrap(),
+            start_addr: block.start as *const u32,
+            end_addr: (block.start + block.size()) as *const u32,
+            rights: self.rights,
+            accessible: true,
+        })
+    }
+
+    /// Gives back the block `block_id` returned by `alloc`, merging it with its free buddies.
+    pub fn free(&mut self, block_id: MemBlockId) -> Result<(), BuddyError> {
+        let mut index = self
+            .blocks
+            .iter()
+            .position(|b| matches!(b, Some(b) if !b.is_free() && b.id == block_id.id()))
+            .ok_or(BuddyError::UnknownBlock)?;
+        self.blocks[index].as_mut().unwrap().handle = Some(block_id);
+
+        loop {
+            let block = self.blocks[index].as_ref().unwrap();
+            if block.order == self.max_order {
+                return Ok(());
+            }
+            let (start, order) = (block.start, block.order);
+            let buddy_start = self.base + ((start - self.base) ^ block.size());
+            let Some(buddy_index) = self.blocks.iter().position(|b| {
+                matches!(b, Some(b) if b.is_free() && b.start == buddy_start && b.order == order)
+            }) else {
+                return Ok(());
+            };
+
+            let (lower, upper) = if start < buddy_start {
+                (index, buddy_index)
+            } else {
+                (buddy_index, index)
+            };
+            let lower_id = self.blocks[lower].as_mut().unwrap().handle.take().unwrap();
+            let upper_id = self.blocks[upper].as_mut().unwrap().handle.take().unwrap();
+            match self.cutter.merge(lower_id, upper_id) {
+                Ok(merged_id) => {
+                    let merged = self.blocks[lower].as_mut().unwrap();
+                    merged.id = merged_id.id();
+                    merged.handle = Some(merged_id);
+                    merged.order += 1;
+                    self.blocks[upper] = None;
+                    index = lower;
+                }
+                Err((lower_id, upper_id)) => {
+                    self.blocks[lower].as_mut().unwrap().handle = Some(lower_id);
+                    self.blocks[upper].as_mut().unwrap().handle = Some(upper_id);
+                    return Err(BuddyError::Pip);
+                }
+            }
+        }
+    }
+
+    /// The total size of the free blocks.
+    pub fn free_size(&self) -> usize {
+        self.blocks
+            .iter()
+            .flatten()
+            .filter(|b| b.is_free())
+            .map(|b| b.size())
+            .sum()
+    }
+
+    /// The size of the largest block `alloc` can currently return.
+    pub fn largest_free(&self) -> usize {
+        self.blocks
+            .iter()
+            .flatten()
+            .filter(|b| b.is_free())
+            .map(|b| b.size())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The blocks pip would hold, as (local id, start, exclusive end).
+    struct MockBlocks {
+        blocks: Vec<(usize, usize, usize)>,
+        next_id: usize,
+    }
+
+    impl MockBlocks {
+        fn new(start: usize, end: usize) -> Self {
+            Self {
+                blocks: vec![(1, start, end)],
+                next_id: 2,
+            }
+        }
+
+        fn position(&self, block_id: &MemBlockId) -> Option<usize> {
+            self.blocks.iter().position(|(id, ..)| *id == block_id.id())
+        }
+
+        fn sorted(&self) -> Vec<(usize, usize)> {
+            let mut blocks: Vec<_> = self.blocks.iter().map(|b| (b.1, b.2)).collect();
+            blocks.sort();
+            blocks
+        }
+    }
+
+    impl BlockCutter for MockBlocks {
+        fn cut(
+            &mut self,
+            block_id: MemBlockId,
+            addr: usize,
+        ) -> Result<(MemBlockId, MemBlockId), MemBlockId> {
+            match self.position(&block_id) {
+                Some(index) if self.blocks[index].1 < addr && addr < self.blocks[index].2 => {
+                    let end = self.blocks[index].2;
+                    self.blocks[index].2 = addr;
+                    self.blocks.push((self.next_id, addr, end));
+                    self.next_id += 1;
+                    Ok((block_id, MemBlockId::new(self.next_id - 1)))
+                }
+                _ => Err(block_id),
+            }
+        }
+
+        fn merge(
+            &mut self,
+            lower_id: MemBlockId,
+            upper_id: MemBlockId,
+        ) -> Result<MemBlockId, (MemBlockId, MemBlockId)> {
+            match (self.position(&lower_id), self.position(&upper_id)) {
+                (Some(lower), Some(upper)) if self.blocks[lower].2 == self.blocks[upper].1 => {
+                    self.blocks[lower].2 = self.blocks[upper].2;
+                    self.blocks.remove(upper);
+                    Ok(lower_id)
+                }
+                _ => Err((lower_id, upper_id)),
+            }
+        }
+    }
+
+    /// A RAM block from `start` to `end_addr`, as pip gives it.
+    fn ram(start: usize, end_addr: usize) -> Block {
+        Block {
+            local_id: MemBlockId::new(1),
+            start_addr: start as *const u32,
+            end_addr: end_addr as *const u32,
+            rights: AccessRights::RW,
+            accessible: true,
+        }
+    }
+
+    #[test]
+    fn new_takes_the_whole_block_whichever_end_pip_gives() {
+        for end_addr in [0x2000, 0x1fff] {
+            let allocator =
+                BuddyAllocator::with_cutter(ram(0x1000, end_addr), MockBlocks::new(0x1000, 0x2000))
+                    .unwrap();
+            assert_eq!((allocator.base, allocator.max_order), (0x1000, 12));
+            assert!(allocator.head_block_id.is_none() && allocator.tail_block_id.is_none());
+            assert_eq!(allocator.free_size(), 0x1000);
+        }
+    }
+
+    #[test]
+    fn new_leaves_the_unaligned_head_and_tail() {
+        let allocator =
+            BuddyAllocator::with_cutter(ram(0x1020, 0x2040), MockBlocks::new(0x1020, 0x2040))
+                .unwrap();
+        assert_eq!((allocator.base, allocator.max_order), (0x1800, 11));
+        assert!(allocator.head_block_id.is_some() && allocator.tail_block_id.is_some());
+        assert_eq!(
+            allocator.cutter.sorted(),
+            [(0x1020, 0x1800), (0x1800, 0x2000), (0x2000, 0x2040)]
+        );
+    }
+
+    #[test]
+    fn alloc_splits_down_to_an_aligned_block() {
+        let mut allocator =
+            BuddyAllocator::with_cutter(ram(0x1000, 0x2000), MockBlocks::new(0x1000, 0x2000))
+                .unwrap();
+        let first = allocator.alloc(100).unwrap();
+        let second = allocator.alloc(32).unwrap();
+
+        assert_eq!((first.start_addr as usize, first.size()), (0x1000, 0x80));
+        assert_eq!((second.start_addr as usize, second.size()), (0x1080, 0x20));
+        assert_eq!(
+            allocator.cutter.sorted(),
+            [
+                (0x1000, 0x1080),
+                (0x1080, 0x10a0),
+                (0x10a0, 0x10c0),
+                (0x10c0, 0x1100),
+                (0x1100, 0x1200),
+                (0x1200, 0x1400),
+                (0x1400, 0x1800),
+                (0x1800, 0x2000),
+            ]
+        );
+        assert_eq!(allocator.free_size(), 0x1000 - 0xa0);
+        assert_eq!(allocator.largest_free(), 0x800);
+    }
+
+    #[test]
+    fn free_merges_buddies_back() {
+        let mut allocator =
+            BuddyAllocator::with_cutter(ram(0x1000, 0x2000), MockBlocks::new(0x1000, 0x2000))
+                .unwrap();
+        let first = allocator.alloc(32).unwrap();
+        let second = allocator.alloc(32).unwrap();
+
+        allocator.free(first.local_id).unwrap();
+        // The buddy of the first block is allocated, nothing can be merged yet.
+        assert_eq!(allocator.largest_free(), 0x800);
+        assert_eq!(allocator.cutter.blocks.len(), 8);
+
+        allocator.free(second.local_id).unwrap();
+        assert_eq!(allocator.cutter.sorted(), [(0x1000, 0x2000)]);
+        assert_eq!(allocator.largest_free(), 0x1000);
+        assert!(matches!(
+            allocator.free(MemBlockId::new(1)),
+            Err(BuddyError::UnknownBlock)
+        ));
+    }
+
+    #[test]
+    fn alloc_refuses_blocks_larger_than_the_area() {
+        let mut allocator =
+            BuddyAllocator::with_cutter(ram(0x1000, 0x2000), MockBlocks::new(0x1000, 0x2000))
+                .unwrap();
+        assert!(matches!(
+            allocator.alloc(0x1001),
+            Err(BuddyError::OutOfMemory)
+        ));
+        assert!(matches!(
+            BuddyAllocator::with_cutter(ram(0x1000, 0x1010), MockBlocks::new(0x1000, 0x1010)),
+            Err(BuddyError::TooSmall)
+        ));
+    }
+}
@@ -0,0 +1,533 @@
+use crate::board::{Board, CurrentBoard};
+use crate::config;
+use crate::core::pip_items::{BoardContext, ContextKind, ExtendedContext, Frame, Interface, VIDT};
+use crate::manage_partition::partition_items::{CreateReturn, DeleteInfos, Parent, Partition};
+use crate::rust::pip_rust_items::{AccessRights, Block, KernStructId, MpuRegion, PartDescId};
+use crate::rust::pip_rust_mpu;
+use crate::tools;
+use core::mem;
+use ptr_bits_ops::PtrBitsOps;
+
+// build.rs already checks partitions.toml, these guard the values against the structures here.
+const _: () = assert!(config::VIDT_SIZE >= mem::size_of::<VIDT>());
+const _: () = assert!(config::VIDT_SIZE >= <CurrentBoard as Board>::VIDT_SIZE);
+const _: () = assert!(config::MPU_REGION_COUNT >= 5); // m_map_partition uses the regions 0 to 4.
+const _: () = assert!(config::MPU_REGION_COUNT <= <CurrentBoard as Board>::MPU_REGION_COUNT);
+
+// The MPU regions of a child m_map_partition maps its blocks in.
+pub const STACK_VIDT_REGION: MpuRegion = MpuRegion::new(0).unwrap();
+pub const CTX_ITF_REGION: MpuRegion = MpuRegion::new(1).unwrap();
+pub const ROM_REGION: MpuRegion = MpuRegion::new(2).unwrap();
+pub const UNUSED_RAM_REGION: MpuRegion = MpuRegion::new(3).unwrap();
+pub const UNUSED_ROM_REGION: MpuRegion = MpuRegion::new(4).unwrap();
+
+/*
++-----+                         +-----+                         +-----+
+|/////| pip reserved block      |\ \ \| parent reserved block   |     | child block
++-----+                         +-----+                         +-----+
+
+RAM
+
+Parent ram
+/!\ This is an arbitrary representation /!\
+#parent ram might be separated in multiple and non contiguous blocks
+#the optional pip block could be before child_ram_block or in a completely different block
++-----------------------------+
+|\ \ \ \ \ \ \ \ \ \ \ \ \ \ \|
+| \ \ \ \ \ \ \ \ \ \ \ \ \ \ |                 child_ram_block
++-----------------------------+_ _ _            +---------------------------------+
+|\ \ \ \ \ \ \ \ \ \ \ \ \ \ \|                 | \ \<optional> ram_head_block \ \| -> |Resultant of stack vidt alignment
+|                             |     |           +---------------------------------+
+|                             |                 |        stack_vidt_block         |
+|                             |     |           +---------------------------------+
+|                             |             |   |         ctx_itf_block           |
+|       child_ram_block       |     |--->   |   +---------------------------------+
+|                             |             |   |                                 |
+|                             |     |           |   <optional> unused_ram_block   | -> |Could be no unused_ram_block if the if the given
+|                             |                 |                                 |    |child_ram_block just fits the stack_vidt & ctx_itf
+|                             |     |           +---------------------------------+
+|                             |_ _ _            |//////<optional> pip_block///////| -> |If no pip block is given to create the partition
++-----------------------------+                 +---------------------------------+
+|\ \ \ \ \ \ \ \ \ \ \ \ \ \ \|
+| \ \ \ \ \ \ \ \ \ \ \ \ \ \ |
+|\ \ \ \ \ \ \ \ \ \ \ \ \ \ \|                 pip_block
++-----------------------------+_ _ _            +---------------------------------+
+|/////////////////////////////|             |   |///////////pd_block_id///////////|
+|////<optional> pip_block/////|     |--->   |   +---------------------------------+ -> |pd and kernel structure of the child partition
+|/////////////////////////////|_ _ _        |   |//////////kern_block_id//////////|
++-----------------------------+                 +---------------------------------+
+|\ \ \ \ \ \ \ \ \ \ \ \ \ \ \|
+| \ \ \ \ \ \ \ \ \ \ \ \ \ \ |
++-----------------------------+
+|//<optional> new_kern_block//| -> |If creating a new kernel structure is required to prepare the new partition's blocks
++-----------------------------+
+
+___________________________________________________________________________________
+ROM
+
+Parent rom
+(arbitrary representation, parent rom might be separated in multiple and non contiguous blocks)
++-----------------------------+                     +-----------------------------+
+|\ \ \ \ \ \ \ \ \ \ \ \ \ \ \|                     |\ \ \ \ \ \ \ \ \ \ \ \ \ \ \|
+| \ \ \ \ rom block X \ \ \ \ |                     | \ \ \ \ \ \ \ \ \ \ \ \ \ \ |
+|\ \ \ \ \ \ \ \ \ \ \ \ \ \ \|                     |\ \ \ \ \ \ \ \ \ \ \ \ \ \ \|
++-----------------------------+                     +-----------------------------+
+|\ \ \ \ \ \ \ \ \ \ \ \ \ \ \|                     |\ \ \ \ \ \ \ \ \ \ \ \ \ \ \|
+| \ \ \ \ \ \ \ \ \ \ \ \ \ \ |                     | \<optional> rom_head_block\ |
+|\ \ \ \ \ \ \ \ \ \ \ \ \ \ \|                     |\ \ \ \ \ \ \ \ \ \ \ \ \ \ \|
+| \ \ \ \ \ \ \ \ \ \ \ \ \ \ | <-- entry_point --> +-----------------------------+
+|\ \ \ \ \ \ \ \ \ \ \ \ \ \ \|         |           |                             |
+| \ \ \ \ \ \ \ \ \ \ \ \ \ \ |    used_rom_size    |          rom_block          |
+|\ \ \ \ \ \ \ \ \ \ \ \ \ \ \|         |           |                             |
+| \ \ \ \ \ \ \ \ \ \ \ \ \ \ |       --+--         +-----------------------------+
+|\ \ \ \ \rom block Y\ \ \ \ \|         |           |                             |
+| \ \ \ \ \ \ \ \ \ \ \ \ \ \ |   unused_rom_size   | <optional> unused_rom_block |
+|\ \ \ \ \ \ \ \ \ \ \ \ \ \ \|         |           |                             |
+| \ \ \ \ \ \ \ \ \ \ \ \ \ \ |       --+--         +-----------------------------+
+|\ \ \ \ \ \ \ \ \ \ \ \ \ \ \|                     |\ \ \ \ \ \ \ \ \ \ \ \ \ \ \|
+| \ \ \ \ \ \ \ \ \ \ \ \ \ \ |                     | \<optional> rom_tail_block\ |
+|\ \ \ \ \ \ \ \ \ \ \ \ \ \ \|                     |\ \ \ \ \ \ \ \ \ \ \ \ \ \ \|
++-----------------------------+                     +-----------------------------+
+| \ \ \ \ \ \ \ \ \ \ \ \ \ \ |                     | \ \ \ \ \ \ \ \ \ \ \ \ \ \ |
+|\ \ \ \ \rom block Z\ \ \ \ \|                     |\ \ \ \ \ \ \ \ \ \ \ \ \ \ \|
+| \ \ \ \ \ \ \ \ \ \ \ \ \ \ |                     | \ \ \ \ \ \ \ \ \ \ \ \ \ \ |
++-----------------------------+                     +-----------------------------+
+*/
+
+/// The size of the block holding a child's context, followed by its interface.
+pub fn ctx_itf_block_size(context_kind: ContextKind) -> usize {
+    context_kind.size() + mem::size_of::<Interface>()
+}
+
+/// Where `m_create_partition` cuts the child's kernel structure, followed by its partition
+/// descriptor, at the end of `pip_block`, or of the child ram block if no pip block is given.
+pub fn child_kern_addr(pip_block: &Block) -> *const u8 {
+    pip_block
+        .start_addr
+        .add_bits_offset(pip_block.size() - 1023)
+        .bits_align(512)
+        .sub_bits_offset(512)
+}
+
+/// Sets the registers a child starts with in `frame`.
+fn init_frame(frame: &mut impl Frame, itf_addr: *const Interface, entry_point: *const u8, sp: u32) {
+    frame.set_r0(itf_addr as u32);
+    frame.set_pc((entry_point as u32) | 1);
+    frame.set_sp(sp);
+    frame.set_xpsr(0x01000000);
+}
+
+/// Creates a child partition in `child_ram_block` and maps nothing yet, see `m_map_partition`.
+///
+/// Fails if pip refuses one of the cuts, the preparations, the creation or the additions. The
+/// blocks cut until then are left as they are.
+pub fn m_create_partition(
+    parent_itf: &Interface, //Structure describing the initial parent memory layout.
+    parent_ctx: *const ExtendedContext, //The address of the space where the parent's context lies, large enough for either frame
+    child_ram_block: Block,             //The parent's RAM block to use as child's RAM space.
+    pip_block: Option<Block>, //The parent's RAM block to use for pip's intern structure for the child. If none is specified, pip datas will be placed at the end of child_ram_block
+    parent_kern_block_id: Option<KernStructId>, //A kernel structure of the parent kept from a deleted partition, see DeleteInfos. If none is given, a new one is cut before the child's pip datas and prepared.
+    entry_point: *const u8,                     //The entry point in ROM of the child.
+    stack_size: usize,                          //The desired size of the child's stack
+    vidt_size: usize, //The vidt size, depends on the architecrure. See config::VIDT_SIZE.
+    context_kind: ContextKind, //The frame of the child's context, EXTENDED if the child uses the FPU.
+    used_rom_size: usize,      //The size of the child's used ROM.
+    unused_rom_size: usize,    //The size of the child's unused ROM.
+) -> Result<CreateReturn, ()> {
+    let parent_pd_block_id = PartDescId::current(parent_itf);
+    // ________________________________
+    //
+    // PREPARE AND INITIALIZE ADDRESSES
+    // ________________________________
+
+    // PIP
+
+    //The pip blocks are cut at the end of the block given in parameters of m_create_partition.
+    //If this block is None, they are cut at the end of the general child ram block.
+
+    //Child first kernel structure address
+    let kern_addr = child_kern_addr(pip_block.as_ref().unwrap_or(&child_ram_block));
+
+    //Child partition descriptor address
+    let pd_addr = kern_addr.add_bits_offset(512);
+
+    //Parent new kernel structure address, the start of the pip datas unless a kernel structure is kept
+    let parent_kern_addr = kern_addr.sub_bits_offset(512);
+    let pip_start_addr = match parent_kern_block_id {
+        Some(_) => kern_addr,
+        None => parent_kern_addr,
+    };
+
+    // CHILD
+
+    // Stack and vidt - Always a physical block
+    let stack_vidt_block_size =
+        tools::next_pow_of_2((stack_size + vidt_size).try_into().map_err(|_| ())?) as usize;
+
+    let stack_addr = child_ram_block.start_addr.bits_align(stack_vidt_block_size); // Set the stack address to the next aligned block with a minimum size of stack_size + vidt_size
+    let vidt_addr = stack_addr.add_bits_offset(stack_size);
+
+    // Context and interface - Might be a virtual block
+    let ctx_itf_block_size = ctx_itf_block_size(context_kind);
+
+    let ctx_addr = stack_addr
+        .add_bits_offset(stack_vidt_block_size)
+        .bits_align(32);
+    let itf_addr = ctx_addr.add_bits_offset(context_kind.size()) as *mut Interface;
+
+    // Unused ram, general purpose within child - Might be a virtual block
+    let unused_ram_addr = ctx_addr.add_bits_offset(ctx_itf_block_size).bits_align(32);
+
+    // Rom
+    let unused_rom_addr = entry_point.add_bits_offset(used_rom_size);
+    let rom_end_addr = unused_rom_addr.add_bits_offset(unused_rom_size);
+
+    tools::memset(parent_itf.vidt_start as *mut u8, 0, mem::size_of::<VIDT>());
+    unsafe {
+        (*(parent_itf.vidt_start as *mut VIDT)).contexts[0] = parent_ctx as *const u8;
+    }
+    tools::memset(vidt_addr as *mut u8, 0, mem::size_of::<VIDT>());
+    unsafe {
+        (*(vidt_addr as *mut VIDT)).contexts[0] = ctx_addr;
+    }
+
+    //INIT CHILD INTERFACE
+    let ram_end_addr = child_ram_block
+        .start_addr
+        .add_bits_offset(child_ram_block.size());
+    unsafe {
+        (*itf_addr).stack_top = vidt_addr.add_bits_offset(4);
+        (*itf_addr).stack_limit = stack_addr;
+        (*itf_addr).vidt_start = vidt_addr;
+        (*itf_addr).vidt_end = vidt_addr.add_bits_offset(vidt_size);
+        (*itf_addr).entry_point = entry_point;
+        (*itf_addr).unused_rom_start = unused_rom_addr;
+        (*itf_addr).rom_end = rom_end_addr;
+        (*itf_addr).unused_ram_start = unused_ram_addr as *mut u8;
+        (*itf_addr).ram_end = ram_end_addr;
+    }
+
+    //INIT CHILD CONTEXT
+    let sp = vidt_addr as u32 - 4;
+    unsafe {
+        match context_kind {
+            ContextKind::BASIC => {
+                let ctx = ctx_addr as *mut BoardContext;
+                ctx.write(BoardContext::default());
+                init_frame(&mut (*ctx).frame, itf_addr, entry_point, sp);
+                (*ctx).is_basic_frame = BoardContext::IS_BASIC_FRAME as u32;
+            }
+            ContextKind::EXTENDED => {
+                let ctx = ctx_addr as *mut ExtendedContext;
+                ctx.write(ExtendedContext::default());
+                init_frame(&mut (*ctx).frame, itf_addr, entry_point, sp);
+                (*ctx).is_basic_frame = 0;
+            }
+        }
+    }
+
+    // __________________________
+    //
+    // CUT THE PARTITION'S BLOCKS
+    // __________________________
+
+    // PIP BLOCKS
+
+    let child_ram_start_addr = child_ram_block.start_addr;
+
+    //  * `child_ram_block_id` is what is left of the child ram block once the pip blocks are cut
+    //at its end, if no pip block was given.
+    //  * `pip_head_block_id` is the part of the given pip block before the pip blocks, if any.
+    let (child_ram_block_id, pip_head_block_id, pip_blocks_id) = match pip_block {
+        None => {
+            let (ram_block_id, pip_blocks_id) = pip_rust_mpu::cut_memory_block(
+                child_ram_block.local_id,
+                pip_start_addr as *const u32,
+                None,
+            )
+            .map_err(|_| ())?;
+            (ram_block_id, None, pip_blocks_id)
+        }
+        Some(block) if block.start_addr as *const u8 == pip_start_addr => {
+            (child_ram_block.local_id, None, block.local_id)
+        }
+        Some(block) => {
+            let (pip_head_block_id, pip_blocks_id) =
+                pip_rust_mpu::cut_memory_block(block.local_id, pip_start_addr as *const u32, None)
+                    .map_err(|_| ())?;
+            (
+                child_ram_block.local_id,
+                Some(pip_head_block_id),
+                pip_blocks_id,
+            )
+        }
+    };
+
+    // parent new kernel structure, prepared before the child's blocks are cut
+    let (parent_kern_block_id, kern_block_id) = match parent_kern_block_id {
+        Some(parent_kern_block_id) => (parent_kern_block_id, pip_blocks_id),
+        None => {
+            let (parent_kern_block_id, kern_block_id) =
+                pip_rust_mpu::cut_memory_block(pip_blocks_id, kern_addr as *const u32, None)
+                    .map_err(|_| ())?;
+            let parent_kern_block_id =
+                pip_rust_mpu::prepare(&parent_pd_block_id, None, parent_kern_block_id)
+                    .map_err(|_| ())?;
+            (parent_kern_block_id, kern_block_id)
+        }
+    };
+
+    // child's first kernel structure and partition descriptor
+    let (kern_block_id, pd_block_id) =
+        pip_rust_mpu::cut_memory_block(kern_block_id, pd_addr as *const u32, None)
+            .map_err(|_| ())?;
+
+    // CHILD BLOCKS
+
+    // Ram blocks
+
+    //  * `stack_vidt_vlock_id` is the local id of the given child ram block if its start address is already aligned on stack/vidt size.
+    //The first aligned block in child's ram block otherwise. It's the block containing the stack & vidt of the child's partition.
+    //  * `ram_head_block_id` is the local id of the left over head if the start_address of the given child's ram block isn't aligned on the vidt/stack block's size.
+    //i.e. the block between the child's block start address and the first address aligned on vidt/stack size
+    let (ram_head_block_id, stack_vidt_block_id) =
+        if stack_addr == child_ram_start_addr as *const u8 {
+            (None, child_ram_block_id)
+        } else {
+            let (ram_head_block_id, stack_vidt_block_id) =
+                pip_rust_mpu::cut_memory_block(child_ram_block_id, stack_addr as *const u32, None)
+                    .map_err(|_| ())?;
+            (Some(ram_head_block_id), stack_vidt_block_id)
+        };
+
+    //The local id of the block containing the context & interface of the partition.
+    let (stack_vidt_block_id, ctx_itf_block_id) =
+        pip_rust_mpu::cut_memory_block(stack_vidt_block_id, ctx_addr as *const u32, None)
+            .map_err(|_| ())?;
+    //The left over tail once the vidt/stack and context/interface blocks have been cut. General purpose within the child partition.
+    let (ctx_itf_block_id, unused_ram_block_id_option) = if unused_ram_addr < ram_end_addr {
+        let (ctx_itf_block_id, unused_ram_block_id) =
+            pip_rust_mpu::cut_memory_block(ctx_itf_block_id, unused_ram_addr as *const u32, None)
+                .map_err(|_| ())?;
+        (ctx_itf_block_id, Some(unused_ram_block_id))
+    } else {
+        (ctx_itf_block_id, None)
+    };
+
+    // Rom blocks
+    let parent_rom_block_attr =
+        pip_rust_mpu::find_block(&parent_pd_block_id, entry_point as *const u32).map_err(|_| ())?;
+    let parent_rom_end_addr = parent_rom_block_attr.end_addr as *const u8;
+
+    //  * `rom_block_id` is the local id of one of parent's rom block if its start address is the entry point of the child,
+    //The local id of the newly cut block whose start address is the entry point of the child otherwise.
+    //  * `rom_head_block_id` is the local id of the left over head if the entry point address is not the start address of the block containing it.
+    //i.e. if the parent's block has to be cut
+    let (rom_head_block_id, rom_block_id) =
+        if parent_rom_block_attr.start_addr == entry_point as *const u32 {
+            (None, parent_rom_block_attr.local_id)
+        } else {
+            let (rom_head_block_id, rom_block_id) = pip_rust_mpu::cut_memory_block(
+                parent_rom_block_attr.local_id,
+                entry_point as *const u32,
+                None,
+            )
+            .map_err(|_| ())?;
+            (Some(rom_head_block_id), rom_block_id)
+        };
+
+    //The left over tail, depending on the requested amount of rom. General purpose within the child partition.
+    let (rom_block_id, unused_rom_block_id_option) = if unused_rom_addr < parent_rom_end_addr {
+        let (rom_block_id, unused_rom_block_id) =
+            pip_rust_mpu::cut_memory_block(rom_block_id, unused_rom_addr as *const u32, None)
+                .map_err(|_| ())?;
+        (rom_block_id, Some(unused_rom_block_id))
+    } else {
+        (rom_block_id, None)
+    };
+
+    //The left over tail, depending on the requested amount of rom. General purpose within the PARENT partition.
+    //Without unused rom, the block cut at the end of the used rom already is this tail.
+    let (unused_rom_block_id_option, rom_end_block_id) = match unused_rom_block_id_option {
+        Some(rom_end_block_id) if rom_end_addr == unused_rom_addr => (None, Some(rom_end_block_id)),
+        Some(unused_rom_block_id) if rom_end_addr < parent_rom_end_addr => {
+            let (unused_rom_block_id, rom_end_block_id) = pip_rust_mpu::cut_memory_block(
+                unused_rom_block_id,
+                rom_end_addr as *const u32,
+                None,
+            )
+            .map_err(|_| ())?;
+            (Some(unused_rom_block_id), Some(rom_end_block_id))
+        }
+        unused_rom_block_id_option => (unused_rom_block_id_option, None),
+    };
+
+    // __________________________________
+    //
+    // CREATE PARTITION AND ASSIGN BLOCKS
+    // __________________________________
+    let pd_block_id = pip_rust_mpu::create_partition(pd_block_id).map_err(|_| ())?;
+    let kern_block_id = pip_rust_mpu::prepare(&pd_block_id, None, kern_block_id).map_err(|_| ())?;
+
+    let child_stack_vidt_block_id =
+        pip_rust_mpu::add_memory_block(&pd_block_id, stack_vidt_block_id, AccessRights::RW)
+            .map_err(|_| ())?;
+    let child_ctx_itf_block_id =
+        pip_rust_mpu::add_memory_block(&pd_block_id, ctx_itf_block_id, AccessRights::RW)
+            .map_err(|_| ())?;
+    let child_unused_ram_block_id_option = unused_ram_block_id_option
+        .map(|x| pip_rust_mpu::add_memory_block(&pd_block_id, x, AccessRights::RW).map_err(|_| ()))
+        .transpose()?;
+
+    let child_rom_block_id =
+        pip_rust_mpu::add_memory_block(&pd_block_id, rom_block_id, AccessRights::RX)
+            .map_err(|_| ())?;
+    let child_unused_rom_block_id_option = unused_rom_block_id_option
+        .map(|x| pip_rust_mpu::add_memory_block(&pd_block_id, x, AccessRights::RX).map_err(|_| ()))
+        .transpose()?;
+
+    let partition = Partition::new(
+        child_stack_vidt_block_id,
+        child_ctx_itf_block_id,
+        child_rom_block_id,
+        child_unused_ram_block_id_option,
+        child_unused_rom_block_id_option,
+    );
+
+    pip_rust_mpu::set_vidt(&pd_block_id, vidt_addr as *const u32).map_err(|_| ())?;
+
+    let parent_infos = Parent::new(
+        ram_head_block_id,
+        rom_head_block_id,
+        rom_end_block_id,
+        pip_head_block_id,
+        Some(parent_kern_block_id),
+        pd_block_id,
+        kern_block_id,
+    );
+
+    Ok(CreateReturn::new(partition, parent_infos))
+}
+
+pub fn m_map_partition(partition_full_infos: &CreateReturn) {
+    let pd_block_id = &partition_full_infos.parent_infos.pd_block_id;
+    let partition = &partition_full_infos.partition;
+
+    pip_rust_mpu::map_mpu(
+        pd_block_id,
+        partition.stack_vidt_block_id.child_block_id(),
+        STACK_VIDT_REGION,
+    )
+    .unwrap();
+    pip_rust_mpu::map_mpu(
+        pd_block_id,
+        partition.ctx_itf_block_id.child_block_id(),
+        CTX_ITF_REGION,
+    )
+    .unwrap();
+    pip_rust_mpu::map_mpu(
+        pd_block_id,
+        partition.rom_block_id.child_block_id(),
+        ROM_REGION,
+    )
+    .unwrap();
+
+    if let Some(x) = &partition.unused_ram_block_id {
+        pip_rust_mpu::map_mpu(pd_block_id, x.child_block_id(), UNUSED_RAM_REGION).unwrap()
+    }
+
+    if let Some(x) = &partition.unused_rom_block_id {
+        pip_rust_mpu::map_mpu(pd_block_id, x.child_block_id(), UNUSED_ROM_REGION).unwrap()
+    }
+}
+
+/// Deletes the partition, giving its blocks back to the parent.
+///
+/// The blocks of the partition are removed from it, then merged back with the blocks they were
+/// cut from, see `DeleteInfos`. The kernel structure the parent prepared to create the partition
+/// is kept, to be given back to `m_create_partition` when creating a partition in the same RAM.
+pub fn m_delete_partition(partition_full_infos: CreateReturn) -> DeleteInfos {
+    let CreateReturn {
+        partition,
+        parent_infos,
+    } = partition_full_infos;
+    let pd_id = &parent_infos.pd_block_id;
+    let _ = pip_rust_mpu::unset_vidt(pd_id);
+
+    let unused_rom_block_id = partition.unused_rom_block_id.map(|block| {
+        pip_rust_mpu::unmap_mpu(pd_id, UNUSED_ROM_REGION).unwrap();
+        pip_rust_mpu::remove_memory_block(block).unwrap()
+    });
+
+    let unused_ram_block_id = partition.unused_ram_block_id.map(|block| {
+        pip_rust_mpu::unmap_mpu(pd_id, UNUSED_RAM_REGION).unwrap();
+        pip_rust_mpu::remove_memory_block(block).unwrap()
+    });
+
+    pip_rust_mpu::unmap_mpu(pd_id, ROM_REGION).unwrap();
+    pip_rust_mpu::unmap_mpu(pd_id, CTX_ITF_REGION).unwrap();
+    pip_rust_mpu::unmap_mpu(pd_id, STACK_VIDT_REGION).unwrap();
+
+    let stack_vidt_block_id =
+        pip_rust_mpu::remove_memory_block(partition.stack_vidt_block_id).unwrap();
+    let ctx_itf_block_id = pip_rust_mpu::remove_memory_block(partition.ctx_itf_block_id).unwrap();
+    let rom_block_id = pip_rust_mpu::remove_memory_block(partition.rom_block_id).unwrap();
+
+    // The child's kernel structure is empty once its blocks are removed, collect gives it back.
+    let kern_block_id = pip_rust_mpu::collect(pd_id).unwrap();
+    let pd_block_id = pip_rust_mpu::delete_partition(parent_infos.pd_block_id).unwrap();
+
+    // MERGE RAM
+
+    // Base Ram
+
+    let ram_block_id = match parent_infos.ram_head_block_id {
+        Some(block) => pip_rust_mpu::merge_memory_blocks(block, stack_vidt_block_id, None).unwrap(),
+        _ => stack_vidt_block_id,
+    };
+
+    let ram_block_id =
+        pip_rust_mpu::merge_memory_blocks(ram_block_id, ctx_itf_block_id, None).unwrap();
+
+    let ram_block_id = match unused_ram_block_id {
+        Some(block) => pip_rust_mpu::merge_memory_blocks(ram_block_id, block, None).unwrap(),
+        _ => ram_block_id,
+    };
+
+    // Pip Ram
+
+    let pip_ram_block_id =
+        pip_rust_mpu::merge_memory_blocks(kern_block_id, pd_block_id, None).unwrap();
+
+    // Tries to merge pip & base ram. Might not work, and still be valid, as the pip block might or might not have been built within child block.
+    // The parent's kernel structure cut between them stays prepared, so they are never merged if one was cut.
+    let (ram_block_id, pip_ram_block_id) =
+        match pip_rust_mpu::merge_memory_blocks(ram_block_id, pip_ram_block_id, None) {
+            Ok(block) => (block, None),
+            Err((ram_block_id, pip_ram_block_id)) => (ram_block_id, Some(pip_ram_block_id)),
+        };
+
+    // MERGE ROM
+
+    let rom_block_id = match parent_infos.rom_head_block_id {
+        Some(block) => pip_rust_mpu::merge_memory_blocks(block, rom_block_id, None).unwrap(),
+        _ => rom_block_id,
+    };
+
+    let rom_block_id = match unused_rom_block_id {
+        Some(block) => pip_rust_mpu::merge_memory_blocks(rom_block_id, block, None).unwrap(),
+        _ => rom_block_id,
+    };
+
+    let rom_block_id = match parent_infos.rom_tail_block_id {
+        Some(block) => pip_rust_mpu::merge_memory_blocks(rom_block_id, block, None).unwrap(),
+        _ => rom_block_id,
+    };
+
+    DeleteInfos::new(
+        rom_block_id,
+        ram_block_id,
+        pip_ram_block_id,
+        parent_infos.new_kern_block_id,
+    )
+}
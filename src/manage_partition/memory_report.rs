@@ -0,0 +1,300 @@
+use crate::core::pip_items::Interface;
+use crate::rust::pip_rust_items::{AccessRights, Block, MemBlockId, PartDescId};
+use crate::rust::pip_rust_mpu;
+use core::fmt;
+
+/// The number of blocks a report lists, the totals covering every block anyway.
+pub const MAX_REPORT_BLOCKS: usize = 32;
+
+/// Blocks are at least as large as the smallest MPU region.
+pub(crate) const MIN_BLOCK_SIZE: usize = 32;
+
+/// Where a walk of `[addr, end)` goes on from `addr`, an address it found no block at: the first
+/// of `known_starts` within `(addr, end)`, else `end`.
+pub(crate) fn next_known_start(addr: usize, end: usize, known_starts: &[usize]) -> usize {
+    known_starts
+        .iter()
+        .copied()
+        .filter(|start| addr < *start && *start < end)
+        .min()
+        .unwrap_or(end)
+        .clamp(addr + MIN_BLOCK_SIZE, end.max(addr + MIN_BLOCK_SIZE))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockUsage {
+    FREE,  //Accessible and not given to any child.
+    CHILD, //Given to, or shared with, a child.
+    PIP, //Not accessible anymore, holds pip structures (partition descriptors, kernel structures).
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ReportEntry {
+    pub start: usize,
+    pub end: usize, //Exclusive
+    pub rights: AccessRights,
+    pub accessible: bool,
+    pub usage: BlockUsage,
+}
+
+impl ReportEntry {
+    pub fn size(&self) -> usize {
+        self.end - self.start
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MemoryTotals {
+    pub free: usize,
+    pub child: usize,
+    pub pip: usize,
+    pub unmapped: usize,     //Addresses pip found no block at.
+    pub largest_free: usize, //The largest run of contiguous free blocks.
+}
+
+/// The blocks of a partition's unused RAM and unused ROM, as `find_block` sees them.
+pub struct MemoryReport {
+    pub ram: [Option<ReportEntry>; MAX_REPORT_BLOCKS],
+    pub rom: [Option<ReportEntry>; MAX_REPORT_BLOCKS],
+    pub ram_totals: MemoryTotals,
+    pub rom_totals: MemoryTotals,
+    pub truncated: bool, //More blocks were found than the report lists.
+}
+
+/// Walks `[start, end)` block by block, filling `entries` and returning the totals.
+///
+/// `find_block` finds the walked partition's block at an address. `is_child_block` tells the blocks
+/// given to children apart from the free ones, the addresses with no block are skipped up to the
+/// next of `known_starts`: they are no block of the partition, probing it again would not tell
+/// more.
+fn walk(
+    find_block: &impl Fn(usize) -> Result<Block, ()>,
+    start: usize,
+    end: usize,
+    is_child_block: &impl Fn(&MemBlockId) -> bool,
+    known_starts: &[usize],
+    entries: &mut [Option<ReportEntry>; MAX_REPORT_BLOCKS],
+    truncated: &mut bool,
+) -> MemoryTotals {
+    let mut totals = MemoryTotals::default();
+    let mut free_run = 0;
+    let mut count = 0;
+    let mut addr = start;
+
+    while addr < end {
+        let Ok(block) = find_block(addr) else {
+            let next = next_known_start(addr, end, known_starts);
+            totals.unmapped += next.min(end) - addr;
+            free_run = 0;
+            addr = next;
+            continue;
+        };
+        let entry = ReportEntry {
+            start: addr,
+            end: block.end().min(end).max(addr + MIN_BLOCK_SIZE),
+            rights: block.rights,
+            accessible: block.accessible,
+            usage: if !block.accessible {
+                BlockUsage::PIP
+            } else if is_child_block(&block.local_id) {
+                BlockUsage::CHILD
+            } else {
+                BlockUsage::FREE
+            },
+        };
+
+        match entry.usage {
+            BlockUsage::FREE => {
+                totals.free += entry.size();
+                free_run += entry.size();
+                totals.largest_free = totals.largest_free.max(free_run);
+            }
+            BlockUsage::CHILD => {
+                totals.child += entry.size();
+                free_run = 0;
+            }
+            BlockUsage::PIP => {
+                totals.pip += entry.size();
+                free_run = 0;
+            }
+        }
+        if count < MAX_REPORT_BLOCKS {
+            entries[count] = Some(entry);
+            count += 1;
+        } else {
+            *truncated = true;
+        }
+        addr = entry.end;
+    }
+    totals
+}
+
+impl MemoryReport {
+    /// Walks the unused RAM (`unused_ram_start..ram_end`) and the unused ROM
+    /// (`unused_rom_start..rom_end`) of the partition described by `interface`.
+    ///
+    /// `is_child_block` tells whether a block of the partition was given to a child, see
+    /// `CreateReturn::owns` and `BootedChildren::owns`. `known_starts` are the start addresses of
+    /// blocks the partition knows of, see `BootedChildren::block_starts`: the walk skips to them
+    /// over the addresses pip finds no block at.
+    pub fn new(
+        interface: &Interface,
+        is_child_block: impl Fn(&MemBlockId) -> bool,
+        known_starts: &[usize],
+    ) -> Self {
+        let pd_block_id = PartDescId::current(interface);
+        let find_block = |addr: usize| pip_rust_mpu::find_block(&pd_block_id, addr as *const u32);
+        let mut report = MemoryReport {
+            ram: [None; MAX_REPORT_BLOCKS],
+            rom: [None; MAX_REPORT_BLOCKS],
+            ram_totals: MemoryTotals::default(),
+            rom_totals: MemoryTotals::default(),
+            truncated: false,
+        };
+        report.ram_totals = walk(
+            &find_block,
+            interface.unused_ram_start as usize,
+            interface.ram_end as usize,
+            &is_child_block,
+            known_starts,
+            &mut report.ram,
+            &mut report.truncated,
+        );
+        report.rom_totals = walk(
+            &find_block,
+            interface.unused_rom_start as usize,
+            interface.rom_end as usize,
+            &is_child_block,
+            known_starts,
+            &mut report.rom,
+            &mut report.truncated,
+        );
+        report
+    }
+}
+
+impl fmt::Display for ReportEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:#010x}-{:#010x} {:>7} {}{} {:?}",
+            self.start,
+            self.end,
+            self.size(),
+            self.rights,
+            if self.accessible { 'a' } else { '-' },
+            self.usage
+        )
+    }
+}
+
+impl fmt::Display for MemoryTotals {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "free {} (largest {}), children {}, pip {}, unmapped {}",
+            self.free, self.largest_free, self.child, self.pip, self.unmapped
+        )
+    }
+}
+
+impl fmt::Display for MemoryReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, entries, totals) in [
+            ("RAM", &self.ram, &self.ram_totals),
+            ("ROM", &self.rom, &self.rom_totals),
+        ] {
+            writeln!(f, "{name}")?;
+            for entry in entries.iter().flatten() {
+                writeln!(f, "  {entry}")?;
+            }
+            writeln!(f, "  {totals}")?;
+        }
+        if self.truncated {
+            writeln!(
+                f,
+                "(more than {MAX_REPORT_BLOCKS} blocks, some are not listed)"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use core::cell::Cell;
+
+    /// The block of `blocks`, as (local id, start, exclusive end, accessible), holding `addr`.
+    pub(crate) fn find_in(
+        blocks: &[(usize, usize, usize, bool)],
+        addr: usize,
+    ) -> Result<Block, ()> {
+        blocks
+            .iter()
+            .find(|(_, start, end, _)| (*start..*end).contains(&addr))
+            .map(|&(id, start, end, accessible)| Block {
+                local_id: MemBlockId::new(id),
+                start_addr: start as *const u32,
+                end_addr: end as *const u32,
+                rights: AccessRights::RW,
+                accessible,
+            })
+            .ok_or(())
+    }
+
+    #[test]
+    fn next_known_start_stays_within_the_walk() {
+        assert_eq!(next_known_start(0x100, 0x400, &[0x80, 0x300, 0x200]), 0x200);
+        assert_eq!(next_known_start(0x100, 0x400, &[0x80, 0x900]), 0x400);
+        assert_eq!(next_known_start(0x100, 0x400, &[0x110]), 0x120);
+    }
+
+    #[test]
+    fn walk_skips_gaps_to_the_next_known_start() {
+        let blocks = [
+            (1, 0x1000, 0x1100, true),
+            (2, 0x1200, 0x1300, true),
+            (3, 0x1300, 0x1400, false),
+        ];
+        let probes = Cell::new(0);
+        let find_block = |addr| {
+            probes.set(probes.get() + 1);
+            find_in(&blocks, addr)
+        };
+        let mut entries = [None; MAX_REPORT_BLOCKS];
+        let mut truncated = false;
+
+        let totals = walk(
+            &find_block,
+            0x1000,
+            0x1400,
+            &|block_id: &MemBlockId| block_id.id() == 2,
+            &[0x1200, 0x9000],
+            &mut entries,
+            &mut truncated,
+        );
+
+        // The gap is probed once, then skipped to the child's block.
+        assert_eq!(probes.get(), 4);
+        let usages: Vec<_> = entries
+            .iter()
+            .flatten()
+            .map(|entry| (entry.start, entry.end, entry.usage))
+            .collect();
+        assert_eq!(
+            usages,
+            [
+                (0x1000, 0x1100, BlockUsage::FREE),
+                (0x1200, 0x1300, BlockUsage::CHILD),
+                (0x1300, 0x1400, BlockUsage::PIP),
+            ]
+        );
+        assert_eq!(
+            (totals.free, totals.child, totals.pip, totals.unmapped),
+            (0x100, 0x100, 0x100, 0x100)
+        );
+        assert_eq!(totals.largest_free, 0x100);
+        assert!(!truncated);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod buddy_allocator;
+pub mod manage_partition;
+pub mod memory_report;
+pub mod mpu_table;
+pub mod partition_items;
@@ -0,0 +1,280 @@
+use crate::board::{Board, CurrentBoard, MemoryRange};
+use crate::manage_partition::manage_partition::{CTX_ITF_REGION, ROM_REGION, STACK_VIDT_REGION};
+use crate::manage_partition::memory_report::{next_known_start, MIN_BLOCK_SIZE};
+use crate::rust::pip_rust_items::{AccessRights, Block, MemBlockId, MpuRegion, PartDescId};
+use crate::rust::pip_rust_mpu;
+use core::fmt;
+
+/// The regions `m_map_partition` always maps, with the block they hold.
+pub const REQUIRED_REGIONS: [(MpuRegion, &str); 3] = [
+    (STACK_VIDT_REGION, "stack/vidt"),
+    (CTX_ITF_REGION, "ctx/itf"),
+    (ROM_REGION, "rom"),
+];
+
+/// The block a region is mapped to, as `find_block` sees it.
+#[derive(Clone, Copy, Debug)]
+pub struct MappedBlock {
+    pub start: usize,
+    pub end: usize, //Exclusive
+    pub rights: AccessRights,
+    pub accessible: bool,
+}
+
+impl MappedBlock {
+    pub fn size(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn overlaps(&self, other: &MappedBlock) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+}
+
+#[derive(Debug)]
+pub struct MpuEntry {
+    pub region: MpuRegion,
+    pub block_id: MemBlockId, //The local id read_mpu gave, within the partition
+    pub block: Option<MappedBlock>, //None if the partition does not own the block anymore.
+}
+
+/// Every MPU region of a partition, `None` for the regions mapping no block.
+pub struct MpuTable {
+    pub entries: [Option<MpuEntry>; MpuRegion::COUNT],
+}
+
+/// Reads the block mapped in each MPU region of the partition `pd_block_id`, and resolves it into
+/// its bounds and rights.
+///
+/// Pip only gives the local id of a mapped block, so the board's RAM and ROM are walked with
+/// `find_block` until every id is found. The addresses the partition has no block at are skipped
+/// with the blocks of the root `root_pd_block_id`, then with `known_starts`, see
+/// `BootedChildren::block_starts`: they must hold the start of the partition's ROM, which neither
+/// the partition nor the root has a block before. A block the partition no longer owns is never
+/// found, and makes the walk cover the whole RAM and ROM.
+pub fn read_mpu_table(
+    pd_block_id: &PartDescId,
+    root_pd_block_id: &PartDescId,
+    known_starts: &[usize],
+) -> MpuTable {
+    let mut table = MpuTable {
+        entries: core::array::from_fn(|_| None),
+    };
+    for region in MpuRegion::all() {
+        table.entries[region.nb()] =
+            pip_rust_mpu::read_mpu(pd_block_id, region)
+                .ok()
+                .map(|block_id| MpuEntry {
+                    region,
+                    block_id,
+                    block: None,
+                });
+    }
+
+    table.resolve(
+        &[<CurrentBoard as Board>::RAM, <CurrentBoard as Board>::ROM],
+        |addr| pip_rust_mpu::find_block(pd_block_id, addr as *const u32),
+        |addr| pip_rust_mpu::find_block(root_pd_block_id, addr as *const u32),
+        known_starts,
+    );
+    table
+}
+
+impl MpuTable {
+    /// Walks `ranges` with `find_block` until every entry has its block, skipping the addresses
+    /// the partition has no block at to the end of the root's block there, found with
+    /// `find_root_block`, or to the next of `known_starts`.
+    fn resolve(
+        &mut self,
+        ranges: &[MemoryRange],
+        find_block: impl Fn(usize) -> Result<Block, ()>,
+        find_root_block: impl Fn(usize) -> Result<Block, ()>,
+        known_starts: &[usize],
+    ) {
+        for range in ranges {
+            let mut addr = range.start;
+            while addr < range.end() && self.unowned().next().is_some() {
+                let Ok(block) = find_block(addr) else {
+                    addr = match find_root_block(addr) {
+                        Ok(root_block) => root_block.end().max(addr + MIN_BLOCK_SIZE),
+                        Err(()) => next_known_start(addr, range.end(), known_starts),
+                    };
+                    continue;
+                };
+                let mapped = MappedBlock {
+                    start: block.start_addr as usize,
+                    end: block.end().max(addr + MIN_BLOCK_SIZE),
+                    rights: block.rights,
+                    accessible: block.accessible,
+                };
+                for entry in self.entries.iter_mut().flatten() {
+                    if entry.block_id.id() == block.local_id.id() {
+                        entry.block = Some(mapped);
+                    }
+                }
+                addr = mapped.end;
+            }
+        }
+    }
+
+    pub fn entry(&self, region: MpuRegion) -> Option<&MpuEntry> {
+        self.entries[region.nb()].as_ref()
+    }
+
+    /// The regions mapped to a block the partition does not own anymore.
+    pub fn unowned(&self) -> impl Iterator<Item = MpuRegion> + '_ {
+        self.entries
+            .iter()
+            .flatten()
+            .filter(|entry| entry.block.is_none())
+            .map(|entry| entry.region)
+    }
+
+    /// The required regions mapping no block, with the block they should hold.
+    pub fn missing_required(&self) -> impl Iterator<Item = (MpuRegion, &'static str)> + '_ {
+        REQUIRED_REGIONS
+            .into_iter()
+            .filter(|(region, _)| self.entry(*region).is_none())
+    }
+
+    /// The pairs of regions, in order, whose blocks overlap.
+    pub fn overlaps(&self) -> impl Iterator<Item = (MpuRegion, MpuRegion)> + '_ {
+        let mapped = || {
+            self.entries
+                .iter()
+                .flatten()
+                .filter_map(|entry| entry.block.map(|block| (entry.region, block)))
+        };
+        mapped().flat_map(move |(region, block)| {
+            mapped()
+                .filter(move |(other, other_block)| *other > region && block.overlaps(other_block))
+                .map(move |(other, _)| (region, other))
+        })
+    }
+}
+
+impl fmt::Display for MappedBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:#010x}-{:#010x} {:>7} {}{}",
+            self.start,
+            self.end,
+            self.size(),
+            self.rights,
+            if self.accessible { 'a' } else { '-' }
+        )
+    }
+}
+
+impl fmt::Display for MpuTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "MPU")?;
+        for region in MpuRegion::all() {
+            match self.entry(region) {
+                None => writeln!(f, "  {:>2} -", region.nb())?,
+                Some(MpuEntry {
+                    block: Some(block), ..
+                }) => writeln!(f, "  {:>2} {block}", region.nb())?,
+                Some(MpuEntry { block_id, .. }) => writeln!(
+                    f,
+                    "  {:>2} {:#010x} not owned by the partition",
+                    region.nb(),
+                    block_id.id()
+                )?,
+            }
+        }
+        for (region, other) in self.overlaps() {
+            writeln!(f, "  overlap: regions {} and {}", region.nb(), other.nb())?;
+        }
+        for (region, name) in self.missing_required() {
+            writeln!(f, "  missing: region {} ({name})", region.nb())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manage_partition::memory_report::tests::find_in;
+
+    const RAM: MemoryRange = MemoryRange {
+        start: 0x2000_0000,
+        size: 0x1000,
+    };
+    const ROM: MemoryRange = MemoryRange {
+        start: 0,
+        size: 0x10000,
+    };
+
+    /// A table whose required regions map the blocks `ids`, none resolved yet.
+    fn required(ids: [usize; 3]) -> MpuTable {
+        let mut table = MpuTable {
+            entries: core::array::from_fn(|_| None),
+        };
+        for ((region, _), id) in REQUIRED_REGIONS.into_iter().zip(ids) {
+            table.entries[region.nb()] = Some(MpuEntry {
+                region,
+                block_id: MemBlockId::new(id),
+                block: None,
+            });
+        }
+        table
+    }
+
+    /// The bounds of the block mapped in each required region.
+    fn bounds(table: &MpuTable) -> Vec<Option<(usize, usize)>> {
+        REQUIRED_REGIONS
+            .iter()
+            .map(|(region, _)| {
+                table
+                    .entry(*region)
+                    .and_then(|entry| entry.block)
+                    .map(|block| (block.start, block.end))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn resolve_crosses_the_rom_gap_to_the_entry_point() {
+        let child = [
+            (10, 0x2000_0400, 0x2000_0600, true),
+            (11, 0x2000_0600, 0x2000_0640, true),
+            (12, 0x8000, 0x9000, true),
+        ];
+        // Pip's own ROM, before 0x7100, is no block of the root.
+        let root = [
+            (1, 0x2000_0000, 0x2000_0400, true),
+            (2, 0x7100, 0x8000, true),
+        ];
+        let find_root_block = |addr| find_in(&root, addr);
+
+        let mut table = required([10, 11, 12]);
+        table.resolve(
+            &[RAM, ROM],
+            |addr| find_in(&child, addr),
+            find_root_block,
+            &[0x2000_0400, 0x2000_0640, 0x8000],
+        );
+        assert_eq!(
+            bounds(&table),
+            [
+                Some((0x2000_0400, 0x2000_0600)),
+                Some((0x2000_0600, 0x2000_0640)),
+                Some((0x8000, 0x9000)),
+            ]
+        );
+        assert_eq!(table.unowned().count(), 0);
+
+        // Without the entry point, the first miss in the ROM ends the walk.
+        let mut table = required([10, 11, 12]);
+        table.resolve(
+            &[RAM, ROM],
+            |addr| find_in(&child, addr),
+            find_root_block,
+            &[0x2000_0400, 0x2000_0640],
+        );
+        assert_eq!(bounds(&table)[2], None);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::rust::pip_rust_items::{AddedBlockId, KernStructId, MemBlockId, PartDescId};
+
+pub struct Partition {
+    pub stack_vidt_block_id: AddedBlockId, //Block containing the stack & vidt
+    pub ctx_itf_block_id: AddedBlockId,    //Block containing the interface
+    pub rom_block_id: AddedBlockId,        //Block containing the used ROM
+
+    //Branch partition attributes
+    pub unused_ram_block_id: Option<AddedBlockId>, //Block containing the unused RAM, NULL if this partition is a leaf partition
+    pub unused_rom_block_id: Option<AddedBlockId>, //Block containing the unused ROM, NULL if this partition is a leaf partition
+}
+
+impl Partition {
+    pub fn new(
+        stack_vidt: AddedBlockId,
+        ctx_itf: AddedBlockId,
+        rom: AddedBlockId,
+        unused_ram: Option<AddedBlockId>,
+        unused_rom: Option<AddedBlockId>,
+    ) -> Self {
+        Partition {
+            stack_vidt_block_id: stack_vidt,
+            ctx_itf_block_id: ctx_itf,
+            rom_block_id: rom,
+            unused_ram_block_id: unused_ram,
+            unused_rom_block_id: unused_rom,
+        }
+    }
+}
+
+pub struct Parent {
+    pub ram_head_block_id: Option<MemBlockId>, //The remaining part of the provided block after cutting the aligned stack/vidt. None if the provided block was already aligned.
+    pub rom_head_block_id: Option<MemBlockId>, //The remaining part of the rom block containing the entry point address. None if the provided entry address was the start address of its block.
+    pub rom_tail_block_id: Option<MemBlockId>, //The remaining part of the rom block after used and unused ram blocks have been cut.
+    pub pip_head_block_id: Option<MemBlockId>, //The remaining part of the provided pip block before the pip structures. None if they were cut within the child ram block.
+    pub new_kern_block_id: Option<KernStructId>, //The kernel structure of the parent prepared to create the partition, or kept from a deleted one. Given back by m_delete_partition.
+
+    //Merge data - used when deleting a partition to merge it back to its parent
+    //In this partition's life time, these datas are unaccessible as they belong to pip.
+    pub pd_block_id: PartDescId, //Local Id of the block containing the partition descriptor
+    pub kern_block_id: KernStructId, //Local Id of the block containing the kernel structure
+}
+
+impl Parent {
+    pub fn new(
+        ram_head_block_id: Option<MemBlockId>,
+        rom_head_block_id: Option<MemBlockId>,
+        rom_tail_block_id: Option<MemBlockId>,
+        pip_head_block_id: Option<MemBlockId>,
+        new_kern_block_id: Option<KernStructId>,
+        pd_block_id: PartDescId,
+        kern_block_id: KernStructId,
+    ) -> Self {
+        Parent {
+            ram_head_block_id,
+            rom_head_block_id,
+            rom_tail_block_id,
+            pip_head_block_id,
+            new_kern_block_id,
+            pd_block_id,
+            kern_block_id,
+        }
+    }
+}
+
+pub struct CreateReturn {
+    pub partition: Partition, //The created partition datas.
+    pub parent_infos: Parent, //The informations of the partition creation related to the parent
+}
+
+impl CreateReturn {
+    pub fn new(partition: Partition, parent_infos: Parent) -> Self {
+        Self {
+            partition,
+            parent_infos,
+        }
+    }
+
+    /// Whether `block_id`, a local id within the parent, was given to the created partition.
+    pub fn owns(&self, block_id: &MemBlockId) -> bool {
+        let child = &self.partition;
+        [
+            Some(&child.stack_vidt_block_id),
+            Some(&child.ctx_itf_block_id),
+            Some(&child.rom_block_id),
+            child.unused_ram_block_id.as_ref(),
+            child.unused_rom_block_id.as_ref(),
+        ]
+        .iter()
+        .flatten()
+        .any(|added| added.block_id().id() == block_id.id())
+    }
+}
+
+pub struct DeleteInfos {
+    pub rom_block_id: MemBlockId,
+    pub ram_block_id: MemBlockId,
+    pub pip_block_id: Option<MemBlockId>, //The partition descriptor and kernel structure merged back, None if merged with ram_block_id.
+    pub parent_kern_block_id: Option<KernStructId>, //The kernel structure the parent prepared for the partition, still prepared. It lies between ram_block_id and pip_block_id.
+}
+
+impl DeleteInfos {
+    pub fn new(
+        rom_block_id: MemBlockId,
+        ram_block_id: MemBlockId,
+        pip_block_id: Option<MemBlockId>,
+        parent_kern_block_id: Option<KernStructId>,
+    ) -> Self {
+        Self {
+            rom_block_id,
+            ram_block_id,
+            pip_block_id,
+            parent_kern_block_id,
+        }
+    }
+}
@@ -1,2 +0,0 @@
-pub mod manage_partition;
-pub mod partition_items;
@@ -1,4 +0,0 @@
-pub mod core;
-pub mod manage_partition;
-pub mod rust;
-pub mod tools;
@@ -0,0 +1,563 @@
+use crate::board::{Board, CurrentBoard};
+use crate::core::pip_items::{
+    BasicContext, BlockAttr, ExtendedContext, Frame, Interface, Reg, YieldCode,
+};
+use core::fmt;
+use core::ops::BitOr;
+
+/// A handle on a memory block of the current partition.
+///
+/// Handles are neither `Clone` nor `Copy`: `cut_memory_block`, `merge_memory_blocks`,
+/// `add_memory_block` and `remove_memory_block` consume the handles they are given and return
+/// the ones that are valid afterwards, so that a stale local id cannot be reused.
+///
+/// `MemBlockId::new`, `find_block`, `read_mpu` and `collect` make a handle from a local id pip gives,
+/// nothing checks another handle on the same block does not exist already.
+#[derive(Debug)]
+pub struct MemBlockId {
+    id: usize,
+}
+
+impl MemBlockId {
+    pub fn new(val: usize) -> MemBlockId {
+        MemBlockId { id: val }
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+}
+
+/// A handle on the block containing a partition descriptor, the current partition's or a child's.
+///
+/// `create_partition` turns a memory block into a child's partition descriptor, and
+/// `delete_partition` gives the memory block back.
+#[derive(Debug)]
+pub struct PartDescId {
+    id: usize,
+}
+
+impl PartDescId {
+    pub fn new(val: usize) -> PartDescId {
+        PartDescId { id: val }
+    }
+
+    /// The partition descriptor of the partition `interface` was given to.
+    pub fn current(interface: &Interface) -> PartDescId {
+        PartDescId::new(interface.part_desc_block_id as usize)
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+}
+
+/// A handle on a block `prepare` turned into a kernel structure.
+#[derive(Debug)]
+pub struct KernStructId {
+    id: usize,
+}
+
+impl KernStructId {
+    pub fn new(val: usize) -> KernStructId {
+        KernStructId { id: val }
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+}
+
+/// The parent of the current partition, as a target of `r#yield` (the null partition descriptor).
+#[derive(Debug, Clone, Copy)]
+pub struct ParentPd;
+
+/// A partition `r#yield` can switch to: the parent, the current partition or one of its children.
+pub trait YieldTarget {
+    /// The local id given to pip, 0 standing for the parent.
+    fn yield_id(&self) -> usize;
+}
+
+impl YieldTarget for ParentPd {
+    fn yield_id(&self) -> usize {
+        0
+    }
+}
+
+impl YieldTarget for PartDescId {
+    fn yield_id(&self) -> usize {
+        self.id
+    }
+}
+
+/// The side of a yield a `YieldError` comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum YieldSide {
+    CALLER, //The current partition: its VIDT index, VIDT or context.
+    CALLEE, //The partition yielded to: its relation to the caller, VIDT index, VIDT or context.
+}
+
+/// Why pip refused a yield, mirroring the error codes of `YieldCode`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum YieldError {
+    CALLEE_INVALID_VIDT_INDEX,
+    CALLER_INVALID_VIDT_INDEX,
+    CALLEE_NOT_CHILD_OF_CALLER,
+    CALLEE_IS_PARENT_OF_ROOT,
+    CALLER_VIDT_IS_NULL,
+    CALLER_VIDT_IS_NOT_PRESENT,
+    CALLER_VIDT_IS_NOT_ACCESSIBLE,
+    CALLER_VIDT_BLOCK_TOO_SMALL,
+    CALLEE_VIDT_IS_NULL,
+    CALLEE_VIDT_IS_NOT_PRESENT,
+    CALLEE_VIDT_IS_NOT_ACCESSIBLE,
+    CALLEE_VIDT_BLOCK_TOO_SMALL,
+    CALLER_CONTEXT_BLOCK_NOT_FOUND,
+    CALLER_CONTEXT_BLOCK_IS_NOT_PRESENT,
+    CALLER_CONTEXT_BLOCK_IS_NOT_ACCESSIBLE,
+    CALLER_CONTEXT_BLOCK_IS_NOT_WRITABLE,
+    CALLER_CONTEXT_EXCEED_BLOCK_END,
+    CALLER_CONTEXT_MISALIGNED,
+    CALLEE_CONTEXT_BLOCK_NOT_FOUND,
+    CALLEE_CONTEXT_BLOCK_IS_NOT_PRESENT,
+    CALLEE_CONTEXT_BLOCK_IS_NOT_ACCESSIBLE,
+    CALLEE_CONTEXT_BLOCK_IS_NOT_READABLE,
+    CALLEE_CONTEXT_EXCEED_BLOCK_END,
+    CALLEE_CONTEXT_MISALIGNED,
+    Unknown(u32), //A value pip is not expected to return.
+}
+
+impl YieldError {
+    /// The result of a yield returning `code`.
+    pub fn check(code: u32) -> Result<(), YieldError> {
+        let yield_code = YieldCode::from_u32(code).ok_or(YieldError::Unknown(code))?;
+        let error = match yield_code {
+            YieldCode::YIELD_SUCCESS => return Ok(()),
+            YieldCode::CALLEE_INVALID_VIDT_INDEX => YieldError::CALLEE_INVALID_VIDT_INDEX,
+            YieldCode::CALLER_INVALID_VIDT_INDEX => YieldError::CALLER_INVALID_VIDT_INDEX,
+            YieldCode::CALLEE_NOT_CHILD_OF_CALLER => YieldError::CALLEE_NOT_CHILD_OF_CALLER,
+            YieldCode::CALLEE_IS_PARENT_OF_ROOT => YieldError::CALLEE_IS_PARENT_OF_ROOT,
+            YieldCode::CALLER_VIDT_IS_NULL => YieldError::CALLER_VIDT_IS_NULL,
+            YieldCode::CALLER_VIDT_IS_NOT_PRESENT => YieldError::CALLER_VIDT_IS_NOT_PRESENT,
+            YieldCode::CALLER_VIDT_IS_NOT_ACCESSIBLE => YieldError::CALLER_VIDT_IS_NOT_ACCESSIBLE,
+            YieldCode::CALLER_VIDT_BLOCK_TOO_SMALL => YieldError::CALLER_VIDT_BLOCK_TOO_SMALL,
+            YieldCode::CALLEE_VIDT_IS_NULL => YieldError::CALLEE_VIDT_IS_NULL,
+            YieldCode::CALLEE_VIDT_IS_NOT_PRESENT => YieldError::CALLEE_VIDT_IS_NOT_PRESENT,
+            YieldCode::CALLEE_VIDT_IS_NOT_ACCESSIBLE => YieldError::CALLEE_VIDT_IS_NOT_ACCESSIBLE,
+            YieldCode::CALLEE_VIDT_BLOCK_TOO_SMALL => YieldError::CALLEE_VIDT_BLOCK_TOO_SMALL,
+            YieldCode::CALLER_CONTEXT_BLOCK_NOT_FOUND => YieldError::CALLER_CONTEXT_BLOCK_NOT_FOUND,
+            YieldCode::CALLER_CONTEXT_BLOCK_IS_NOT_PRESENT => {
+                YieldError::CALLER_CONTEXT_BLOCK_IS_NOT_PRESENT
+            }
+            YieldCode::CALLER_CONTEXT_BLOCK_IS_NOT_ACCESSIBLE => {
+                YieldError::CALLER_CONTEXT_BLOCK_IS_NOT_ACCESSIBLE
+            }
+            YieldCode::CALLER_CONTEXT_BLOCK_IS_NOT_WRITABLE => {
+                YieldError::CALLER_CONTEXT_BLOCK_IS_NOT_WRITABLE
+            }
+            YieldCode::CALLER_CONTEXT_EXCEED_BLOCK_END => {
+                YieldError::CALLER_CONTEXT_EXCEED_BLOCK_END
+            }
+            YieldCode::CALLER_CONTEXT_MISALIGNED => YieldError::CALLER_CONTEXT_MISALIGNED,
+            YieldCode::CALLEE_CONTEXT_BLOCK_NOT_FOUND => YieldError::CALLEE_CONTEXT_BLOCK_NOT_FOUND,
+            YieldCode::CALLEE_CONTEXT_BLOCK_IS_NOT_PRESENT => {
+                YieldError::CALLEE_CONTEXT_BLOCK_IS_NOT_PRESENT
+            }
+            YieldCode::CALLEE_CONTEXT_BLOCK_IS_NOT_ACCESSIBLE => {
+                YieldError::CALLEE_CONTEXT_BLOCK_IS_NOT_ACCESSIBLE
+            }
+            YieldCode::CALLEE_CONTEXT_BLOCK_IS_NOT_READABLE => {
+                YieldError::CALLEE_CONTEXT_BLOCK_IS_NOT_READABLE
+            }
+            YieldCode::CALLEE_CONTEXT_EXCEED_BLOCK_END => {
+                YieldError::CALLEE_CONTEXT_EXCEED_BLOCK_END
+            }
+            YieldCode::CALLEE_CONTEXT_MISALIGNED => YieldError::CALLEE_CONTEXT_MISALIGNED,
+        };
+        Err(error)
+    }
+
+    /// The side of the yield the error comes from, `None` for an unknown error.
+    pub fn side(&self) -> Option<YieldSide> {
+        match self {
+            YieldError::CALLER_INVALID_VIDT_INDEX
+            | YieldError::CALLER_VIDT_IS_NULL
+            | YieldError::CALLER_VIDT_IS_NOT_PRESENT
+            | YieldError::CALLER_VIDT_IS_NOT_ACCESSIBLE
+            | YieldError::CALLER_VIDT_BLOCK_TOO_SMALL
+            | YieldError::CALLER_CONTEXT_BLOCK_NOT_FOUND
+            | YieldError::CALLER_CONTEXT_BLOCK_IS_NOT_PRESENT
+            | YieldError::CALLER_CONTEXT_BLOCK_IS_NOT_ACCESSIBLE
+            | YieldError::CALLER_CONTEXT_BLOCK_IS_NOT_WRITABLE
+            | YieldError::CALLER_CONTEXT_EXCEED_BLOCK_END
+            | YieldError::CALLER_CONTEXT_MISALIGNED => Some(YieldSide::CALLER),
+            YieldError::Unknown(_) => None,
+            _ => Some(YieldSide::CALLEE),
+        }
+    }
+
+    pub fn is_caller_side(&self) -> bool {
+        self.side() == Some(YieldSide::CALLER)
+    }
+
+    pub fn is_callee_side(&self) -> bool {
+        self.side() == Some(YieldSide::CALLEE)
+    }
+
+    pub fn message(&self) -> &'static str {
+        match self {
+            YieldError::CALLEE_INVALID_VIDT_INDEX => "The VIDT index of the callee is greater than 32.",
+            YieldError::CALLER_INVALID_VIDT_INDEX => "The VIDT index of the caller is greater than 32.",
+            YieldError::CALLEE_NOT_CHILD_OF_CALLER => "The callee is not a child of the caller.",
+            YieldError::CALLEE_IS_PARENT_OF_ROOT => "The root partition tried to call its parent.",
+            YieldError::CALLER_VIDT_IS_NULL => "The address of the block containing the VIDT of the caller is null.",
+            YieldError::CALLER_VIDT_IS_NOT_PRESENT => "The block containing the VIDT of the caller does not have the present flag.",
+            YieldError::CALLER_VIDT_IS_NOT_ACCESSIBLE => "The block containing the VIDT of the caller does not have the accessible flag.",
+            YieldError::CALLER_VIDT_BLOCK_TOO_SMALL => "The block containing the VIDT of the caller is too small.",
+            YieldError::CALLEE_VIDT_IS_NULL => "The address of the block containing the VIDT of the callee is null.",
+            YieldError::CALLEE_VIDT_IS_NOT_PRESENT => "The block containing the VIDT of the callee does not have the present flag.",
+            YieldError::CALLEE_VIDT_IS_NOT_ACCESSIBLE => "The block containing the VIDT of the callee does not have the accessible flag.",
+            YieldError::CALLEE_VIDT_BLOCK_TOO_SMALL => "The block containing the VIDT of the callee is too small.",
+            YieldError::CALLER_CONTEXT_BLOCK_NOT_FOUND => "No block were found in the caller's address space that match the context address read from the VIDT.",
+            YieldError::CALLER_CONTEXT_BLOCK_IS_NOT_PRESENT => "The block containing the address to which the context of the caller is to be written does not have the present flag.",
+            YieldError::CALLER_CONTEXT_BLOCK_IS_NOT_ACCESSIBLE => "The block containing the address to which the context of the caller is to be written does not have the accessible flag.",
+            YieldError::CALLER_CONTEXT_BLOCK_IS_NOT_WRITABLE => "The block containing the address to which the context of the caller is to be written does not have the writable flag.",
+            YieldError::CALLER_CONTEXT_EXCEED_BLOCK_END => "The address of the caller's context, added to the size of a context, exceeds the end of the block.",
+            YieldError::CALLER_CONTEXT_MISALIGNED => "The address to which the caller's context should be written is not aligned on a 4-byte boundary.",
+            YieldError::CALLEE_CONTEXT_BLOCK_NOT_FOUND => "No block were found in the callee's address space that match the context address read from the VIDT.",
+            YieldError::CALLEE_CONTEXT_BLOCK_IS_NOT_PRESENT => "The block containing the address at which the context of the callee is to be read does not have the present flag.",
+            YieldError::CALLEE_CONTEXT_BLOCK_IS_NOT_ACCESSIBLE => "The block containing the address at which the context of the callee is to be read does not have the accessible flag.",
+            YieldError::CALLEE_CONTEXT_BLOCK_IS_NOT_READABLE => "The block containing the address at which the context of the callee is to be read does not have the readable flag.",
+            YieldError::CALLEE_CONTEXT_EXCEED_BLOCK_END => "The address of the callee's context, added to the size of a context, exceeds the end of the block.",
+            YieldError::CALLEE_CONTEXT_MISALIGNED => "The address at which the callee's context should be read is not aligned on a 4-byte boundary.",
+            YieldError::Unknown(_) => "Pip returned an unknown yield code.",
+        }
+    }
+}
+
+impl fmt::Display for YieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            YieldError::Unknown(code) => write!(f, "{} ({code})", self.message()),
+            _ => f.write_str(self.message()),
+        }
+    }
+}
+
+/// A block of the current partition added to a child with `add_memory_block`.
+///
+/// The block stays in the current partition, shared with the child, until `remove_memory_block`
+/// gives its handle back.
+#[derive(Debug)]
+pub struct AddedBlockId {
+    block_id: MemBlockId,       //The block within the current partition
+    child_block_id: MemBlockId, //The local id of the block within the child
+}
+
+impl AddedBlockId {
+    pub(crate) fn new(block_id: MemBlockId, child_block_id: MemBlockId) -> Self {
+        Self {
+            block_id,
+            child_block_id,
+        }
+    }
+
+    pub fn block_id(&self) -> &MemBlockId {
+        &self.block_id
+    }
+
+    pub fn child_block_id(&self) -> &MemBlockId {
+        &self.child_block_id
+    }
+
+    pub(crate) fn into_block_id(self) -> MemBlockId {
+        self.block_id
+    }
+}
+
+/// The access rights of a block, packed as pip expects them: `(r << 2) | (w << 1) | x`.
+///
+/// `add_memory_block` refuses to give a child a block both writable and executable (W^X), unless
+/// the rights were built with `allow_write_exec`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccessRights {
+    bits: u32,
+}
+
+impl AccessRights {
+    pub const NONE: AccessRights = AccessRights { bits: 0 };
+    pub const READ: AccessRights = AccessRights { bits: 0b100 };
+    pub const WRITE: AccessRights = AccessRights { bits: 0b010 };
+    pub const EXEC: AccessRights = AccessRights { bits: 0b001 };
+
+    pub const RO: AccessRights = Self::READ;
+    pub const RW: AccessRights = Self::READ.union(Self::WRITE);
+    pub const RX: AccessRights = Self::READ.union(Self::EXEC);
+    pub const RWX: AccessRights = Self::RW.union(Self::EXEC);
+
+    /// Not a right, set when the W^X check is disabled.
+    const ALLOW_WRITE_EXEC: u32 = 0b1000;
+
+    /// Rights packed as `(r << 2) | (w << 1) | x`, plus `0b1000` if the W^X check is disabled, as
+    /// in the boot manifest. Other bits are ignored.
+    pub const fn from_bits_truncate(bits: u32) -> AccessRights {
+        AccessRights {
+            bits: bits & 0b1111,
+        }
+    }
+
+    pub const fn from_flags(read: bool, write: bool, exec: bool) -> AccessRights {
+        AccessRights {
+            bits: (read as u32) << 2 | (write as u32) << 1 | exec as u32,
+        }
+    }
+
+    /// The rights, as given to pip.
+    pub const fn bits(self) -> u32 {
+        self.bits & 0b111
+    }
+
+    pub const fn union(self, other: AccessRights) -> AccessRights {
+        AccessRights {
+            bits: self.bits | other.bits,
+        }
+    }
+
+    pub const fn contains(self, other: AccessRights) -> bool {
+        self.bits() & other.bits() == other.bits()
+    }
+
+    pub const fn read(self) -> bool {
+        self.contains(Self::READ)
+    }
+
+    pub const fn write(self) -> bool {
+        self.contains(Self::WRITE)
+    }
+
+    pub const fn exec(self) -> bool {
+        self.contains(Self::EXEC)
+    }
+
+    /// The same rights, without the W^X check: the block may be both writable and executable.
+    pub const fn allow_write_exec(self) -> AccessRights {
+        AccessRights {
+            bits: self.bits | Self::ALLOW_WRITE_EXEC,
+        }
+    }
+
+    /// Whether the rights are both writable and executable while the W^X check is enabled.
+    pub const fn violates_write_xor_exec(self) -> bool {
+        self.write() && self.exec() && self.bits & Self::ALLOW_WRITE_EXEC == 0
+    }
+}
+
+impl BitOr for AccessRights {
+    type Output = AccessRights;
+
+    fn bitor(self, other: AccessRights) -> AccessRights {
+        self.union(other)
+    }
+}
+
+impl From<&BlockAttr> for AccessRights {
+    fn from(block_attr: &BlockAttr) -> Self {
+        AccessRights::from_flags(
+            block_attr.read & 1 == 1,
+            block_attr.write & 1 == 1,
+            block_attr.exec & 1 == 1,
+        )
+    }
+}
+
+impl fmt::Display for AccessRights {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (set, c) in [(self.read(), 'r'), (self.write(), 'w'), (self.exec(), 'x')] {
+            write!(f, "{}", if set { c } else { '-' })?;
+        }
+        Ok(())
+    }
+}
+
+/// A physical MPU region number, below the board's `MPU_REGION_COUNT`.
+///
+/// The system calls taking an optional region take an `Option<MpuRegion>`, `None` standing for
+/// pip's `-1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MpuRegion {
+    nb: u8,
+}
+
+const _: () = assert!(MpuRegion::COUNT <= u8::MAX as usize);
+
+impl MpuRegion {
+    /// The number of MPU regions of the board.
+    pub const COUNT: usize = <CurrentBoard as Board>::MPU_REGION_COUNT;
+
+    /// The region `nb`, `None` if the board has no such region.
+    pub const fn new(nb: usize) -> Option<MpuRegion> {
+        if nb < Self::COUNT {
+            Some(MpuRegion { nb: nb as u8 })
+        } else {
+            None
+        }
+    }
+
+    pub const fn nb(self) -> usize {
+        self.nb as usize
+    }
+
+    /// Every region of the board, in order.
+    pub fn all() -> impl Iterator<Item = MpuRegion> {
+        (0..Self::COUNT).map(|nb| MpuRegion { nb: nb as u8 })
+    }
+
+    /// The region number given to pip, -1 meaning no region.
+    pub(crate) fn raw(region: Option<MpuRegion>) -> i32 {
+        region.map_or(-1, |region| region.nb as i32)
+    }
+}
+
+/// Blocks start and end on 32 bytes, the smallest MPU region.
+const BLOCK_ALIGN: usize = 32;
+
+pub struct Block {
+    pub local_id: MemBlockId,
+    pub start_addr: *const u32,
+    pub end_addr: *const u32,
+    pub rights: AccessRights,
+    pub accessible: bool,
+}
+
+impl Block {
+    pub fn from_core(block_attr: BlockAttr) -> Self {
+        Self {
+            local_id: MemBlockId::new(block_attr.local_id as usize),
+            start_addr: block_attr.start_addr,
+            end_addr: block_attr.end_addr,
+            rights: AccessRights::from(&block_attr),
+            accessible: block_attr.accessible & 1 == 1,
+        }
+    }
+
+    pub fn new() -> Self {
+        Self {
+            local_id: MemBlockId::new(0),
+            start_addr: core::ptr::null(),
+            end_addr: core::ptr::null(),
+            rights: AccessRights::NONE,
+            accessible: false,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.end() - self.start_addr as usize
+    }
+
+    /// The exclusive end of the block, whether pip gives its last address or the one after it.
+    pub fn end(&self) -> usize {
+        (self.end_addr as usize + 1) & !(BLOCK_ALIGN - 1)
+    }
+}
+
+impl Default for Block {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Why a context pointer was refused, mirroring the context errors `r#yield` returns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContextError {
+    BLOCK_NOT_FOUND,         //The context does not start within the given block.
+    BLOCK_IS_NOT_ACCESSIBLE, //The block does not have the accessible flag.
+    BLOCK_IS_NOT_READABLE,   //The block does not have the readable flag.
+    EXCEED_BLOCK_END, //The address of the context, added to its size, exceeds the end of the block.
+    MISALIGNED,       //The context is not aligned on a 4-byte boundary.
+}
+
+/// A context saved by pip, such as the ones the VIDT slots point to, holding a basic or an
+/// extended frame as told by its `is_basic_frame` field.
+pub enum ContextRef<'a> {
+    Basic(&'a BasicContext),
+    Extended(&'a ExtendedContext),
+}
+
+impl<'a> ContextRef<'a> {
+    /// Reads the context at `ctx`, which must lie within `block`, with the checks pip does before
+    /// restoring a context.
+    ///
+    /// `block` should be found with `find_block` on the current partition, so that its bounds and
+    /// rights are pip's.
+    pub fn from_raw(ctx: *const u8, block: &'a Block) -> Result<ContextRef<'a>, ContextError> {
+        let addr = ctx as usize;
+        if addr < block.start_addr as usize || addr >= block.end() {
+            return Err(ContextError::BLOCK_NOT_FOUND);
+        }
+        if !block.accessible {
+            return Err(ContextError::BLOCK_IS_NOT_ACCESSIBLE);
+        }
+        if !block.rights.read() {
+            return Err(ContextError::BLOCK_IS_NOT_READABLE);
+        }
+
+        let fits = |size: usize| addr.checked_add(size).is_some_and(|end| end <= block.end());
+        if !fits(core::mem::size_of::<BasicContext>()) {
+            return Err(ContextError::EXCEED_BLOCK_END);
+        }
+        if !addr.is_multiple_of(4) {
+            return Err(ContextError::MISALIGNED);
+        }
+        let basic = unsafe { &*(ctx as *const BasicContext) };
+        if basic.is_basic_frame != 0 {
+            return Ok(ContextRef::Basic(basic));
+        }
+        if !fits(core::mem::size_of::<ExtendedContext>()) {
+            return Err(ContextError::EXCEED_BLOCK_END);
+        }
+        Ok(ContextRef::Extended(unsafe {
+            &*(ctx as *const ExtendedContext)
+        }))
+    }
+
+    pub fn is_basic_frame(&self) -> bool {
+        matches!(self, ContextRef::Basic(_))
+    }
+
+    pub fn pip_flags(&self) -> u32 {
+        match self {
+            ContextRef::Basic(ctx) => ctx.pip_flags,
+            ContextRef::Extended(ctx) => ctx.pip_flags,
+        }
+    }
+
+    /// The value of `reg` in the saved frame, `None` for the FPU registers of a basic frame.
+    pub fn get(&self, reg: Reg) -> Option<u32> {
+        match self {
+            ContextRef::Basic(ctx) => ctx.frame.get(reg),
+            ContextRef::Extended(ctx) => ctx.frame.get(reg),
+        }
+    }
+
+    /// Every register of the saved frame with its value.
+    pub fn registers(&self) -> impl Iterator<Item = (Reg, u32)> + 'a {
+        let (basic, extended) = match *self {
+            ContextRef::Basic(ctx) => (Some(ctx.frame.iter()), None),
+            ContextRef::Extended(ctx) => (None, Some(ctx.frame.iter())),
+        };
+        basic
+            .into_iter()
+            .flatten()
+            .chain(extended.into_iter().flatten())
+    }
+}
+
+impl fmt::Display for ContextRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "pip_flags {:#010x}", self.pip_flags())?;
+        match self {
+            ContextRef::Basic(ctx) => write!(f, "{}", ctx.frame),
+            ContextRef::Extended(ctx) => write!(f, "{}", ctx.frame),
+        }
+    }
+}
@@ -1,8 +1,9 @@
-use crate::pip_mpu::core::pip_core_mpu;
-use crate::pip_mpu::core::pip_items::BlockAttr;
-use crate::pip_mpu::core::pip_items::BlockOrError;
-use crate::pip_mpu::core::pip_items::YieldCode;
-use crate::pip_mpu::rust::pip_rust_items::{Block, BlockId};
+use crate::core::pip_core_mpu;
+use crate::core::pip_items::BlockOrError;
+use crate::rust::pip_rust_items::{
+    AccessRights, AddedBlockId, Block, KernStructId, MemBlockId, MpuRegion, PartDescId, YieldError,
+    YieldTarget,
+};
 
 /// Brief.
 ///     Creates a new child
@@ -12,20 +13,20 @@ use crate::pip_mpu::rust::pip_rust_items::{Block, BlockId};
 ///     current partition), e.g. initializes the block `block_local_id` as a PD block and
 ///     sets the current partition as the parent partition.
 ///
-/// *   `block_local_id` - The block to become the child partition descriptor
+/// *   `block_local_id` - The block to become the child partition descriptor, consumed
 ///
 /// Returns
 ///     A Result such as in case of :
-///         - Success   : Empty Ok()
-///         - Error     : Empty Err()
+///         - Success   : Ok() containing the child partition descriptor
+///         - Error     : Err() giving back the block
 /// ____
 /// Note: This function refers to createPartition from pip-core-mpu
 /// see https://gitlab.univ-lille.fr/2xs/pip/pipcore-mpu/-/blob/master/src/core/Services.v?ref_type=heads#L54-125
-pub fn create_partition(block_local_id: &BlockId) -> Result<(), ()> {
+pub fn create_partition(block_local_id: MemBlockId) -> Result<PartDescId, MemBlockId> {
     if (pip_core_mpu::pip_create_partition(block_local_id.id() as *const u32) & 1) == 1 {
-        Ok(())
+        Ok(PartDescId::new(block_local_id.id()))
     } else {
-        Err(())
+        Err(block_local_id)
     }
 }
 
@@ -34,35 +35,40 @@ pub fn create_partition(block_local_id: &BlockId) -> Result<(), ()> {
 ///
 /// Description.
 ///     The [cutMemoryBlock] system call cuts the memory block `block_to_cut_local_id`
-/// 	at `cut_addr` which creates a new subbblock at that address.
+///     at `cut_addr` which creates a new subbblock at that address.
 ///     The new subblock is placed in the physical MPU region of the current partition
-///     if the `mpu_region_nb` is a valid region number.
+///     if `mpu_region_nb` is given.
 ///
-/// *   `block_to_cut_local_id` - The local id of the block to cut
+/// *   `block_to_cut_local_id` - The block to cut, consumed
 /// *   `cut_addr`              - The adress at which to create the new subblock
-/// *   `mpu_region_nb`         - The mpu region number
+/// *   `mpu_region_nb`         - The mpu region, None to leave the subblock unmapped
 ///
 /// Returns
 ///     A Result such as in case of :
-///         - Success   : Ok() containing the newly created subblock's local id
-///         - Error     : Empty Err()
+///         - Success   : Ok() containing the block before `cut_addr` (same local id as the cut
+///                       block) and the newly created subblock
+///         - Error     : Err() giving back the block to cut
 /// ____
 /// Note: This function refers to cutMemoryBlock from pip-core-mpu
 /// see https://gitlab.univ-lille.fr/2xs/pip/pipcore-mpu/-/blob/master/src/core/Services.v?ref_type=heads#L127-233
 pub fn cut_memory_block(
-    block_to_cut_local_id: &BlockId,
+    block_to_cut_local_id: MemBlockId,
     cut_addr: *const u32,
-    mpu_region_nb: Option<i32>,
-) -> Result<BlockId, ()> {
+    mpu_region_nb: Option<MpuRegion>,
+) -> Result<(MemBlockId, MemBlockId), MemBlockId> {
     let subblock_local_id = pip_core_mpu::pip_cut_memory_block(
         block_to_cut_local_id.id() as *const u32,
         cut_addr,
-        mpu_region_nb.unwrap_or_else(|| -1),
+        MpuRegion::raw(mpu_region_nb),
     );
-    subblock_local_id
-        .is_null()
-        .then(|| BlockId::new(subblock_local_id as usize))
-        .ok_or(())
+    if subblock_local_id.is_null() {
+        Err(block_to_cut_local_id)
+    } else {
+        Ok((
+            block_to_cut_local_id,
+            MemBlockId::new(subblock_local_id as usize),
+        ))
+    }
 }
 
 /// Brief.
@@ -73,37 +79,34 @@ pub fn cut_memory_block(
 ///     `block_to_merge_2_local_id` together.
 ///     The two blocks have been cut before so @block_to_merge_1_local_id < @block_to_merge_2_local_id.
 ///     The merged block is placed in the physical MPU region of the current partition
-///     if the `mpu_region_nb` is a valid region number.
+///     if `mpu_region_nb` is given.
 ///
-/// *   `block_to_merge_1_local_id` - The local id of the first block to merge
-/// *   `block_to_merge_2_local_id` - The local id of the second block to merge
-/// *   `mpu_region_nb`             - The mpu region number
+/// *   `block_to_merge_1_local_id` - The first block to merge, consumed
+/// *   `block_to_merge_2_local_id` - The second block to merge, consumed
+/// *   `mpu_region_nb`             - The mpu region, None to leave the merged block unmapped
 ///
 /// Returns
 ///     A Result such as in case of :
-///         - Success   : Ok() containing the newly created merged block's local id
-///         - Error     : Empty Err()
+///         - Success   : Ok() containing the newly created merged block
+///         - Error     : Err() giving back both blocks
 /// ____
 /// Note: This function refers to mergeMemoryBlocks from pip-core-mpu
 /// see https://gitlab.univ-lille.fr/2xs/pip/pipcore-mpu/-/blob/master/src/core/Services.v?ref_type=heads#L236-320
 pub fn merge_memory_blocks(
-    block_to_merge_1_local_id: &BlockId,
-    block_to_merge_2_local_id: &BlockId,
-    mpu_region_nb: Option<i32>,
-) -> Result<BlockId, ()> {
-    let fin_mpu_region_nb = match mpu_region_nb {
-        Some(region) => region,
-        _ => -1,
-    };
+    block_to_merge_1_local_id: MemBlockId,
+    block_to_merge_2_local_id: MemBlockId,
+    mpu_region_nb: Option<MpuRegion>,
+) -> Result<MemBlockId, (MemBlockId, MemBlockId)> {
     let merged_block_local_id = pip_core_mpu::pip_merge_memory_blocks(
         block_to_merge_1_local_id.id() as *const u32,
         block_to_merge_2_local_id.id() as *const u32,
-        fin_mpu_region_nb,
+        MpuRegion::raw(mpu_region_nb),
     );
-    merged_block_local_id
-        .is_null()
-        .then(|| BlockId::new(merged_block_local_id as usize))
-        .ok_or(())
+    if merged_block_local_id.is_null() {
+        Err((block_to_merge_1_local_id, block_to_merge_2_local_id))
+    } else {
+        Ok(MemBlockId::new(merged_block_local_id as usize))
+    }
 }
 
 /// Brief.
@@ -111,26 +114,26 @@ pub fn merge_memory_blocks(
 ///     
 /// Description.
 ///     The [prepare] system call prepares the partition of `part_desc_block_id`
-///		(current partition or child) to receive `projected_slots_nb` of blocks and use the
-///		`requisitionned_block_local_id` as a metadata structure IF NEEDED => if there isn't enough
+///        (current partition or child) to receive `projected_slots_nb` of blocks and use the
+///        `requisitionned_block_local_id` as a metadata structure IF NEEDED => if there isn't enough
 ///                                                                     free slots in the current
 ///                                                                     kernel structure.
 ///
 ///     e.g. this will prepare `requisitionned_block_local_id` to be a kernel structure added to the
-///		kernel structure list of the partition `part_desc_block_id`
+///        kernel structure list of the partition `part_desc_block_id`
 ///        - if enough free slots to receive `projected_slots_nb` then won't do anything
-///				- if not enough free slots then prepare the block
+///                - if not enough free slots then prepare the block
 ///        - if `projected_slots_nb` not specified then prepare the block whatever the nb of
-///					free slots
+///                    free slots
 ///
 /// *   `part_desc_block_id`            - The block to prepare within the current or child partition
 /// *   `projected_slots_nb`            - The number of requested slots, 'None' to force prepare
-/// *   `requisitionned_block_local_id` - The block used as the new kernel structure
+/// *   `requisitionned_block_local_id` - The block used as the new kernel structure, consumed
 ///
 /// Returns
 ///     A Result such as in case of :
-///         - Valid Operation   : Empty Ok()
-///         - Unvalid Operation : Empty Err()
+///         - Valid Operation   : Ok() containing the kernel structure
+///         - Unvalid Operation : Err() giving back the block
 ///
 ///     TODO :
 ///     -   Ok(bool) - contains true if the requisitionned block was used, false otherwise
@@ -141,18 +144,22 @@ pub fn merge_memory_blocks(
 ///         This function refers to prepare from pip-core-mpu
 /// see https://gitlab.univ-lille.fr/2xs/pip/pipcore-mpu/-/blob/master/src/core/Services.v?ref_type=heads#L322-468
 pub fn prepare(
-    part_desc_block_id: &BlockId,
+    part_desc_block_id: &PartDescId,
     projected_slots_nb: Option<i32>,
-    requisitionned_block_local_id: &BlockId,
-) -> Result<(), ()> {
+    requisitionned_block_local_id: MemBlockId,
+) -> Result<KernStructId, MemBlockId> {
     let valid = pip_core_mpu::pip_prepare(
         part_desc_block_id.id() as *const u32,
-        projected_slots_nb.unwrap_or_else(|| -1),
+        projected_slots_nb.unwrap_or(-1),
         requisitionned_block_local_id.id() as *const u32,
     ) & 1
         == 1;
 
-    valid.then(|| {}).ok_or(())
+    if valid {
+        Ok(KernStructId::new(requisitionned_block_local_id.id()))
+    } else {
+        Err(requisitionned_block_local_id)
+    }
 }
 
 /// Brief.
@@ -160,40 +167,43 @@ pub fn prepare(
 ///     
 /// Description.
 ///     The [addMemoryBlock] system call adds a block to a child partition.
-///		The block is still accessible from the current partition (shared memory).
+///        The block is still accessible from the current partition (shared memory).
 ///
 /// *   child_part_desc_block_local_id  - The local id of the child partition to share with
-/// *   block_to_share_local_id         - The local id of the block entry address where the block to share lies
-/// *   r                               - The reading rights to apply to the child partition
-/// *   w                               - ----writing---------------------------------------
-/// *   x                               - ----execute---------------------------------------
+/// *   block_to_share_local_id         - The block to share, consumed until it is removed
+/// *   rights                          - The rights to apply to the child partition
 ///
 /// Returns
 ///     A Result such as in case of :
-///         - Success : Ok() containing the local id of the block in the child. (newly "mapped" id)
-///         - Error   : empty Err()
+///         - Success : Ok() containing the shared block, with its local id in the child. (newly "mapped" id)
+///         - Error   : Err() giving back the block to share
+///             `rights` are writable and executable, see `AccessRights::allow_write_exec`
+///             pip refused to add the block
 /// ____
 /// Note: This function refers to addMemoryBlock from pip-core-mpu
 /// see https://gitlab.univ-lille.fr/2xs/pip/pipcore-mpu/-/blob/master/src/core/Services.v?ref_type=heads#L470-555
 pub fn add_memory_block(
-    child_part_desc_block_local_id: &BlockId,
-    block_to_share_local_id: &BlockId,
-    r: bool,
-    w: bool,
-    x: bool,
-) -> Result<BlockId, ()> {
+    child_part_desc_block_local_id: &PartDescId,
+    block_to_share_local_id: MemBlockId,
+    rights: AccessRights,
+) -> Result<AddedBlockId, MemBlockId> {
+    if rights.violates_write_xor_exec() {
+        return Err(block_to_share_local_id);
+    }
     let added_block_local_id = pip_core_mpu::pip_add_memory_block(
         child_part_desc_block_local_id.id() as *const u32,
         block_to_share_local_id.id() as *const u32,
-        r as u32,
-        w as u32,
-        x as u32,
+        rights.bits(),
     );
 
-    added_block_local_id
-        .is_null()
-        .then(|| BlockId::new(added_block_local_id as usize))
-        .ok_or(())
+    if added_block_local_id.is_null() {
+        Err(block_to_share_local_id)
+    } else {
+        Ok(AddedBlockId::new(
+            block_to_share_local_id,
+            MemBlockId::new(added_block_local_id as usize),
+        ))
+    }
 }
 
 /// Brief.
@@ -202,28 +212,33 @@ pub fn add_memory_block(
 ///     The [removeMemoryBlock] system call removes a block from a child partition.
 ///     
 ///     This operation succeeds for any shared memory block previously added, but
-///		fails if the purpose of the block is not shared memory anymore,
-///		in particular in such cases:
+///        fails if the purpose of the block is not shared memory anymore,
+///        in particular in such cases:
 ///        - The block can't be removed if the child or its descendants use it
-///					(or part of it) as a kernel structure
+///                    (or part of it) as a kernel structure
 ///        - The block can't be removed if the child's descendants cut the block
 ///     An unnaccessible block can still be removed if it is cut and all its
 ///     subbblocks are still accessible, == "can be merged back together"
 ///
-/// *   block_to_remove_local_id - The local id of the block entry address where the block to remove lies
+/// *   block_to_remove_local_id - The shared block to remove, consumed
 ///
 /// Returns
 ///     A Result such as in case of :
-///         - Success   : Empty Ok()
-///         - Error     : Empty Err()
+///         - Success   : Ok() containing the block within the current partition
+///         - Error     : Err() giving back the shared block
 /// ____
 /// Note: This function refers to removeMemoryBlock from pip-core-mpu
 /// see https://gitlab.univ-lille.fr/2xs/pip/pipcore-mpu/-/blob/master/src/core/Services.v?ref_type=heads#L573-610
-pub fn remove_memory_block(block_to_remove_local_id: &BlockId) -> Result<(), ()> {
-    if pip_core_mpu::pip_remove_memory_block(block_to_remove_local_id.id() as *const u32) & 1 == 1 {
-        Ok(())
+pub fn remove_memory_block(
+    block_to_remove_local_id: AddedBlockId,
+) -> Result<MemBlockId, AddedBlockId> {
+    if pip_core_mpu::pip_remove_memory_block(block_to_remove_local_id.block_id().id() as *const u32)
+        & 1
+        == 1
+    {
+        Ok(block_to_remove_local_id.into_block_id())
     } else {
-        Err(())
+        Err(block_to_remove_local_id)
     }
 }
 
@@ -232,27 +247,29 @@ pub fn remove_memory_block(block_to_remove_local_id: &BlockId) -> Result<(), ()>
 ///
 /// Description.
 ///     The [deletePartition] system call deletes the partition `child_part_desc_block_local_id`
-///		which is a child of the current partition, e.g. prunes the partition tree by removing
-///		all references of the child and its respective blocks from the current partition.
+///        which is a child of the current partition, e.g. prunes the partition tree by removing
+///        all references of the child and its respective blocks from the current partition.
 ///
-/// *   child_part_desc_block_local_id - The local id of the descriptor block of the child to delete
+/// *   child_part_desc_block_local_id - The descriptor block of the child to delete, consumed
 ///
 /// Returns
 ///     A Result such as in case of :
-///         - Success   : Empty Ok()
-///         - Error     : Empty Err()
+///         - Success   : Ok() containing the block of the partition descriptor
+///         - Error     : Err() giving back the partition descriptor
 ///             Null adress
 ///             Not a child partition
 /// ____
 /// Note: This function refers to deletePartition from pip-core-mpu
 /// see https://gitlab.univ-lille.fr/2xs/pip/pipcore-mpu/-/blob/master/src/core/Services.v?ref_type=heads#L612-663
-pub fn delete_partition(child_part_desc_block_local_id: &BlockId) -> Result<(), ()> {
+pub fn delete_partition(
+    child_part_desc_block_local_id: PartDescId,
+) -> Result<MemBlockId, PartDescId> {
     if pip_core_mpu::pip_delete_partition(child_part_desc_block_local_id.id() as *const u32) & 1
         == 1
     {
-        Ok(())
+        Ok(MemBlockId::new(child_part_desc_block_local_id.id()))
     } else {
-        Err(())
+        Err(child_part_desc_block_local_id)
     }
 }
 
@@ -261,8 +278,8 @@ pub fn delete_partition(child_part_desc_block_local_id: &BlockId) -> Result<(),
 ///
 /// Description.
 ///     The [collect] system call collects an empty structure (if possible) from
-///		the partition `part_desc_block_id` (current partition or a child) and
-///		returns the retrieved block.
+///        the partition `part_desc_block_id` (current partition or a child) and
+///        returns the retrieved block.
 ///
 /// *   part_desc_block_id - The global or local id of the descriptor block of the current or child partition
 ///
@@ -273,12 +290,11 @@ pub fn delete_partition(child_part_desc_block_local_id: &BlockId) -> Result<(),
 /// ____
 /// Note: This function refers to collect from pip-core-mpu
 /// see https://gitlab.univ-lille.fr/2xs/pip/pipcore-mpu/-/blob/master/src/core/Services.v?ref_type=heads#L665-703
-pub fn collect(part_desc_block_id: &BlockId) -> Result<BlockId, ()> {
+pub fn collect(part_desc_block_id: &PartDescId) -> Result<MemBlockId, ()> {
     let collected_block_local_id = pip_core_mpu::pip_collect(part_desc_block_id.id() as *const u32);
 
-    collected_block_local_id
-        .is_null()
-        .then(|| BlockId::new(collected_block_local_id as usize))
+    (!collected_block_local_id.is_null())
+        .then(|| MemBlockId::new(collected_block_local_id as usize))
         .ok_or(())
 }
 
@@ -287,10 +303,10 @@ pub fn collect(part_desc_block_id: &BlockId) -> Result<BlockId, ()> {
 ///
 /// Description.
 ///     The [mapMPU] system call maps the `block_to_map_local_id` block owned by
-///		the partition `part_desc_block_id` (current partition or a child) in the
+///        the partition `part_desc_block_id` (current partition or a child) in the
 ///     `mpu_region_nb` MPU region.
-///		If the block is NULL, then the targeted MPU region is removed from the MPU.
-///		If the block was already mapped, moves the block to the given MPU region.
+///        If the block is NULL, then the targeted MPU region is removed from the MPU.
+///        If the block was already mapped, moves the block to the given MPU region.
 ///
 /// *   part_desc_block_id      - The global or local id of the descriptor block of the current or child partition
 /// *   block_to_map_local_id   - The block to map local id
@@ -303,19 +319,18 @@ pub fn collect(part_desc_block_id: &BlockId) -> Result<BlockId, ()> {
 ///             - No block to map specified                             - block removed from the given region nb
 ///             - `block_to_map_local_id` is not accessible             - block removed from the given region nb
 ///             - `part_desc_block_id` not current nor child partition  - nothing
-///             - `mpu_region_nb` is not a valid region number          - nothing
 /// ____
 /// Note: This function refers to mapMPU from pip-core-mpu
 /// see https://gitlab.univ-lille.fr/2xs/pip/pipcore-mpu/-/blob/master/src/core/Services.v?ref_type=heads#L706-759
 pub fn map_mpu(
-    part_desc_block_id: &BlockId,
-    block_to_map_local_id: &BlockId,
-    mpu_region_nb: i32,
+    part_desc_block_id: &PartDescId,
+    block_to_map_local_id: &MemBlockId,
+    mpu_region_nb: MpuRegion,
 ) -> Result<(), ()> {
     if pip_core_mpu::pip_map_mpu(
         part_desc_block_id.id() as *const u32,
         block_to_map_local_id.id() as *const u32,
-        mpu_region_nb,
+        mpu_region_nb.nb() as i32,
     ) & 1
         == 1
     {
@@ -325,11 +340,11 @@ pub fn map_mpu(
     }
 }
 
-pub fn unmap_mpu(part_desc_block_id: &BlockId, mpu_region_nb: i32) -> Result<(), ()> {
+pub fn unmap_mpu(part_desc_block_id: &PartDescId, mpu_region_nb: MpuRegion) -> Result<(), ()> {
     if pip_core_mpu::pip_map_mpu(
         part_desc_block_id.id() as *const u32,
-        0 as *const u32,
-        mpu_region_nb,
+        core::ptr::null(),
+        mpu_region_nb.nb() as i32,
     ) & 1
         == 1
     {
@@ -344,7 +359,7 @@ pub fn unmap_mpu(part_desc_block_id: &BlockId, mpu_region_nb: i32) -> Result<(),
 ///
 /// Description.
 ///     The [readMPU] system call reads the content of the physical MPU owned by
-///		the partition `part_desc_block_id` (current partition or a child) at the
+///        the partition `part_desc_block_id` (current partition or a child) at the
 ///     `mpu_region_nb` MPU region.
 ///
 /// *   part_desc_block_id  - The global or local id of the descriptor block of the current or child partition
@@ -358,13 +373,17 @@ pub fn unmap_mpu(part_desc_block_id: &BlockId, mpu_region_nb: i32) -> Result<(),
 /// ____
 /// Note: This function refers to readMPU from pip-core-mpu
 /// see https://gitlab.univ-lille.fr/2xs/pip/pipcore-mpu/-/blob/master/src/core/Services.v?ref_type=heads#L762-794
-pub fn read_mpu(part_desc_block_id: &BlockId, mpu_region_nb: i32) -> Result<BlockId, ()> {
-    let block_read_local_id =
-        pip_core_mpu::pip_read_mpu(part_desc_block_id.id() as *const u32, mpu_region_nb);
+pub fn read_mpu(
+    part_desc_block_id: &PartDescId,
+    mpu_region_nb: MpuRegion,
+) -> Result<MemBlockId, ()> {
+    let block_read_local_id = pip_core_mpu::pip_read_mpu(
+        part_desc_block_id.id() as *const u32,
+        mpu_region_nb.nb() as i32,
+    );
 
-    block_read_local_id
-        .is_null()
-        .then(|| BlockId::new(block_read_local_id as usize))
+    (!block_read_local_id.is_null())
+        .then(|| MemBlockId::new(block_read_local_id as usize))
         .ok_or(())
 }
 
@@ -373,7 +392,7 @@ pub fn read_mpu(part_desc_block_id: &BlockId, mpu_region_nb: i32) -> Result<Bloc
 ///
 /// Description.
 ///     The [findBlock] system call finds the block of the provided `addr_in_block`
-///		by searching in the blocks list of the partition descriptor `part_desc_block_id`.
+///        by searching in the blocks list of the partition descriptor `part_desc_block_id`.
 ///     Writes the found block at the `target_block_addr`.
 ///
 /// *   part_desc_block_id  - The global or local id of the descriptor block of the current or child partition
@@ -389,20 +408,20 @@ pub fn read_mpu(part_desc_block_id: &BlockId, mpu_region_nb: i32) -> Result<Bloc
 /// ____
 /// Note: This function refers to findBlock from pip-core-mpu
 /// see https://gitlab.univ-lille.fr/2xs/pip/pipcore-mpu/-/blob/master/src/core/Services.v?ref_type=heads#L796-840
-pub fn find_block(part_desc_block_id: &BlockId, addr_in_block: *const u32) -> Result<Block, ()> {
-    let target_block_addr = BlockOrError::new();
+pub fn find_block(part_desc_block_id: &PartDescId, addr_in_block: *const u32) -> Result<Block, ()> {
+    let mut target_block = BlockOrError::new();
     if pip_core_mpu::pip_find_block(
         part_desc_block_id.id() as *const u32,
         addr_in_block,
-        &(&target_block_addr as *const _),
+        &mut target_block,
     ) & 1
         == 1
     {
         unsafe {
-            if target_block_addr.error == 1 {
+            if target_block.error == -1 {
                 Err(())
             } else {
-                Ok(Block::fromCore(target_block_addr.block_attr))
+                Ok(Block::from_core(target_block.block_attr))
             }
         }
     } else {
@@ -437,7 +456,7 @@ pub fn find_block(part_desc_block_id: &BlockId, addr_in_block: *const u32) -> Re
 /// ____
 /// Note: This function refers to setVIDT from pip-core-mpu
 /// see https://gitlab.univ-lille.fr/2xs/pip/pipcore-mpu/-/blob/master/src/core/Services.v?ref_type=heads#L842-914
-pub fn set_vidt(part_desc_block_id: &BlockId, vidt_address: *const u32) -> Result<(), ()> {
+pub fn set_vidt(part_desc_block_id: &PartDescId, vidt_address: *const u32) -> Result<(), ()> {
     if pip_core_mpu::pip_set_vidt(part_desc_block_id.id() as *const u32, vidt_address) & 1 == 1 {
         Ok(())
     } else {
@@ -445,8 +464,9 @@ pub fn set_vidt(part_desc_block_id: &BlockId, vidt_address: *const u32) -> Resul
     }
 }
 
-pub fn unset_vidt(part_desc_block_id: &BlockId) -> Result<(), ()> {
-    if pip_core_mpu::pip_set_vidt(part_desc_block_id.id() as *const u32, 0 as *const u32) & 1 == 1 {
+pub fn unset_vidt(part_desc_block_id: &PartDescId) -> Result<(), ()> {
+    if pip_core_mpu::pip_set_vidt(part_desc_block_id.id() as *const u32, core::ptr::null()) & 1 == 1
+    {
         Ok(())
     } else {
         Err(())
@@ -460,8 +480,8 @@ pub fn unset_vidt(part_desc_block_id: &BlockId) -> Result<(), ()> {
 ///     The [yield] system call yields from the current partition (the caller)
 ///     to its parent, itself or one of its children (the callee).
 ///
-/// *   callee_part_desc_block_id       -   The local id of the block containing the descriptor structure of the partition to yield to
-///                                         0 means the partition descriptor structure of the parent of the current partition
+/// *   callee_part_desc_block_id       -   The partition descriptor of the partition to yield to
+///                                         `ParentPd` means the partition descriptor structure of the parent of the current partition
 /// *   user_target_interrupt           -   The index of the VIDT which contains the address pointing to the location where the current
 ///                                         context is to be RESTORED
 /// *   user_caller_context_save_index  -   The index of the VIDT which contains the address pointing to the location where the current
@@ -471,7 +491,10 @@ pub fn unset_vidt(part_desc_block_id: &BlockId) -> Result<(), ()> {
 /// *   enable_interrupts_on_wake        -   -----------------------------------------------------------wake
 ///
 /// Returns
-///     An Error code such as
+///     A Result such as in case of :
+///         - Success   : Empty Ok(), once the callee yields back
+///         - Error     : Err() with the `YieldError` matching pip's error code, or `Unknown` if the
+///                       code is not one of these
 ///             BASICS
 ///              1  : The VIDT index of the CALLEE is greater than 32.
 ///              2  : ----------------------CALLER--------------------
@@ -510,14 +533,14 @@ pub fn unset_vidt(part_desc_block_id: &BlockId) -> Result<(), ()> {
 /// Note: This function refers to yield from pip-core-mpu
 /// see https://gitlab.univ-lille.fr/2xs/pip/pipcore-mpu/-/blob/master/src/arch/dwm1001/boot/yield_c.c?ref_type=heads
 pub fn r#yield(
-    callee_part_desc_block_id: &BlockId,
+    callee_part_desc_block_id: &impl YieldTarget,
     user_target_interrupt: u32,
     user_caller_context_save_index: u32,
     enable_interrupts_on_yield: bool,
     enable_interrupts_on_wake: bool,
-) -> YieldCode {
-    YieldCode::from_u32(pip_core_mpu::pip_yield(
-        callee_part_desc_block_id.id() as *const u32,
+) -> Result<(), YieldError> {
+    YieldError::check(pip_core_mpu::pip_yield(
+        callee_part_desc_block_id.yield_id() as *const u32,
         user_target_interrupt,
         user_caller_context_save_index,
         if enable_interrupts_on_yield {
@@ -531,7 +554,6 @@ pub fn r#yield(
             0_u32
         },
     ))
-    .unwrap()
 }
 
 /// Brief.
@@ -553,7 +575,7 @@ pub fn r#yield(
 /// ____
 /// Note: This function refers to getIntState from pip-core-mpu
 /// see https://gitlab.univ-lille.fr/2xs/pip/pipcore-mpu/-/blob/master/src/arch/dwm1001/boot/pip_interrupt_calls.c?ref_type=heads#L40-54
-pub fn child_has_enabled_int(child_part_desc_block_local_id: &BlockId) -> bool {
+pub fn child_has_enabled_int(child_part_desc_block_local_id: &PartDescId) -> bool {
     pip_core_mpu::pip_get_int_state(child_part_desc_block_local_id.id() as *const u32) & 1 == 1
 }
 
@@ -0,0 +1,49 @@
+//! Service calls from a child partition to its parent.
+//!
+//! A child calls a service by yielding to its parent with the request left in r5, r8 and r10,
+//! registers pip saves in the child's context without using them for the yield. Once resumed,
+//! the parent reads the request back from that context with [`ServiceCall::from_frame`].
+
+use crate::core::pip_items::Frame;
+use enum_from_derive::FromU32;
+
+/// Marks r5 as holding a service code, as r5 is garbage after a plain yield.
+pub const SERVICE_TAG: u32 = 0x5E5C_0000;
+const SERVICE_TAG_MASK: u32 = 0xffff_0000;
+
+/// The index of the parent's VIDT restored by a service call, where `m_create_partition` puts
+/// the parent context.
+pub const PARENT_VIDT_INDEX: u32 = 0;
+
+/// The index of the child's VIDT where its context is saved during a service call, the one
+/// restored when the parent yields back to it.
+pub const CHILD_CONTEXT_INDEX: u32 = 0;
+
+#[derive(FromU32, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ServiceCode {
+    /// The child has nothing to do, the parent may schedule another partition.
+    YIELD = 0,
+
+    /// The child panicked, with the line and the column of the panic as arguments.
+    PANIC = 1,
+}
+
+/// A service call, as read by the parent in the saved context of a child.
+#[derive(Clone, Copy, Debug)]
+pub struct ServiceCall {
+    pub code: ServiceCode,
+    pub args: [u32; 2],
+}
+
+impl ServiceCall {
+    /// Reads the service call saved in `frame`, `None` if the child yielded without one.
+    pub fn from_frame(frame: &impl Frame) -> Option<Self> {
+        if frame.r5() & SERVICE_TAG_MASK != SERVICE_TAG {
+            return None;
+        }
+        Some(ServiceCall {
+            code: ServiceCode::from_u32(frame.r5() & !SERVICE_TAG_MASK)?,
+            args: [frame.r8(), frame.r10()],
+        })
+    }
+}
@@ -0,0 +1,63 @@
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+pub fn next_pow_of_2(mut x: u32) -> u32 {
+    x -= 1;
+    x |= x >> 1;
+    x |= x >> 2;
+    x |= x >> 4;
+    x |= x >> 8;
+    x |= x >> 16;
+    x + 1
+}
+
+pub fn memset(s: *mut u8, c: u8, n: usize) {
+    for i in 0..n {
+        unsafe {
+            *(s.wrapping_add(i)) = c;
+        }
+    }
+}
+
+/// A value shared between the partition's threads of execution, reached through `lock` only.
+///
+/// The value comes first, so that a `static` holding it is also the address of the value, as
+/// debug probes expect for the RTT control block.
+#[repr(C)]
+pub struct SpinLock<T> {
+    value: UnsafeCell<T>,
+    locked: AtomicBool,
+}
+
+// The value is only reached with `locked` held.
+unsafe impl<T: Send> Sync for SpinLock<T> {}
+
+impl<T> SpinLock<T> {
+    pub const fn new(value: T) -> Self {
+        Self {
+            value: UnsafeCell::new(value),
+            locked: AtomicBool::new(false),
+        }
+    }
+
+    /// Runs `f` on the value, spinning until no one else holds it.
+    pub fn lock<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+        let result = f(unsafe { &mut *self.value.get() });
+        self.locked.store(false, Ordering::Release);
+        result
+    }
+}
+
+/// Stands for the instructions of `arm_asm!` off ARM, where pip and the debugger can not be
+/// called.
+#[cfg(not(target_arch = "arm"))]
+pub(crate) unsafe fn not_arm() -> ! {
+    panic!("pip and semihosting calls only run on ARM")
+}